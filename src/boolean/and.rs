@@ -1,8 +1,8 @@
 use ark_ff::{Field, PrimeField};
-use ark_relations::gr1cs::SynthesisError;
+use ark_relations::gr1cs::{OptimizationGoal, SynthesisError};
 use ark_std::ops::{BitAnd, BitAndAssign};
 
-use crate::{fields::fp::FpVar, prelude::EqGadget};
+use crate::{fields::fp::FpVar, prelude::EqGadget, GR1CSVar};
 
 use super::Boolean;
 
@@ -27,6 +27,10 @@ impl<F: Field> Boolean<F> {
 impl<F: PrimeField> Boolean<F> {
     /// Outputs `bits[0] & bits[1] & ... & bits.last().unwrap()`.
     ///
+    /// Short inputs are combined with a chain of binary ANDs, and longer ones
+    /// by comparing the sum of the bits to `bits.len()`. Where the cut-off
+    /// lies depends on the optimization goal of the constraint system.
+    ///
     /// ```
     /// # fn main() -> Result<(), ark_relations::gr1cs::SynthesisError> {
     /// // We'll use the BLS12-381 scalar field for our constraints.
@@ -50,7 +54,16 @@ impl<F: PrimeField> Boolean<F> {
     #[tracing::instrument(target = "gr1cs")]
     pub fn kary_and(bits: &[Self]) -> Result<Self, SynthesisError> {
         assert!(!bits.is_empty());
-        if bits.len() <= 3 {
+        // A chain of binary ANDs costs `n - 1` constraints with three nonzero
+        // entries each. Comparing the sum of the bits to `n` costs two
+        // constraints in which the sum is written out twice, or three in which
+        // it is written out once if we are optimizing for weight (see
+        // `FpVar::is_eq`). The latter is lighter only for longer inputs.
+        let max_chain_len = match bits.cs().optimization_goal() {
+            OptimizationGoal::Weight => 5,
+            OptimizationGoal::None | OptimizationGoal::Constraints => 3,
+        };
+        if bits.len() <= max_chain_len {
             let mut cur: Option<Self> = None;
             for next in bits {
                 cur = if let Some(b) = cur {
@@ -194,9 +207,10 @@ mod tests {
         alloc::{AllocVar, AllocationMode},
         boolean::test_utils::run_binary_exhaustive,
         prelude::EqGadget,
+        test_utils::cost_with_goal,
         GR1CSVar,
     };
-    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef};
     use ark_test_curves::bls12_381::Fr;
 
     #[test]
@@ -300,6 +314,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn kary_and_optimization_goal() {
+        for n in [5, 16] {
+            let circuit = |cs: ConstraintSystemRef<Fr>| {
+                let bits = (0..n)
+                    .map(|_| Boolean::new_witness(cs.clone(), || Ok(true)).unwrap())
+                    .collect::<Vec<_>>();
+                assert!(Boolean::kary_and(&bits).unwrap().value().unwrap());
+            };
+            let constraints = cost_with_goal(OptimizationGoal::Constraints, circuit);
+            let weight = cost_with_goal(OptimizationGoal::Weight, circuit);
+            assert!(constraints.0 < weight.0);
+            assert!(weight.1 < constraints.1);
+        }
+    }
+
     #[test]
     fn kary_and() -> Result<(), SynthesisError> {
         // test different numbers of operands
@@ -1,10 +1,11 @@
 use ark_ff::PrimeField;
-use ark_relations::gr1cs::SynthesisError;
+use ark_relations::gr1cs::{OptimizationGoal, SynthesisError};
 use ark_std::ops::{BitOr, BitOrAssign};
 
 use crate::{
     eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
+    GR1CSVar,
 };
 
 use super::Boolean;
@@ -21,6 +22,10 @@ impl<F: PrimeField> Boolean<F> {
 
     /// Outputs `bits[0] | bits[1] | ... | bits.last().unwrap()`.
     ///
+    /// Short inputs are combined with a chain of binary ORs, and longer ones
+    /// by checking that the sum of the bits is nonzero. Where the cut-off lies
+    /// depends on the optimization goal of the constraint system.
+    ///
     /// ```
     /// # fn main() -> Result<(), ark_relations::gr1cs::SynthesisError> {
     /// // We'll use the BLS12-381 scalar field for our constraints.
@@ -45,7 +50,17 @@ impl<F: PrimeField> Boolean<F> {
     #[tracing::instrument(target = "gr1cs")]
     pub fn kary_or(bits: &[Self]) -> Result<Self, SynthesisError> {
         assert!(!bits.is_empty());
-        if bits.len() <= 3 {
+        // A chain of binary ORs costs `n - 1` constraints with six nonzero
+        // entries each. Checking that the sum of the bits is nonzero costs two
+        // constraints in which the sum is written out twice, or three in which
+        // it is written out once if we are optimizing for weight (see
+        // `FpVar::is_neq`). The latter is lighter for all but the shortest
+        // inputs.
+        let max_chain_len = match bits.cs().optimization_goal() {
+            OptimizationGoal::Weight => 2,
+            OptimizationGoal::None | OptimizationGoal::Constraints => 3,
+        };
+        if bits.len() <= max_chain_len {
             let mut cur: Option<Self> = None;
             for next in bits {
                 cur = if let Some(b) = cur {
@@ -153,8 +168,10 @@ mod tests {
         alloc::{AllocVar, AllocationMode},
         boolean::test_utils::run_binary_exhaustive,
         prelude::EqGadget,
+        test_utils::cost_with_goal,
         GR1CSVar,
     };
+    use ark_relations::gr1cs::ConstraintSystemRef;
     use ark_test_curves::bls12_381::Fr;
 
     #[test]
@@ -179,4 +196,20 @@ mod tests {
         })
         .unwrap()
     }
+
+    #[test]
+    fn kary_or_optimization_goal() {
+        for n in [8, 16] {
+            let circuit = |cs: ConstraintSystemRef<Fr>| {
+                let bits = (0..n)
+                    .map(|i| Boolean::new_witness(cs.clone(), || Ok(i == n - 1)).unwrap())
+                    .collect::<Vec<_>>();
+                assert!(Boolean::kary_or(&bits).unwrap().value().unwrap());
+            };
+            let constraints = cost_with_goal(OptimizationGoal::Constraints, circuit);
+            let weight = cost_with_goal(OptimizationGoal::Weight, circuit);
+            assert!(constraints.0 < weight.0);
+            assert!(weight.1 < constraints.1);
+        }
+    }
 }
@@ -1,14 +1,13 @@
 use super::{
     params::{get_params, OptimizationType},
-    reduce::{bigint_to_basefield, limbs_to_bigint, Reducer},
-    AllocatedMulResultVar,
+    public_input::{packed_chunk_size, params_for_goal, to_packed_public_input},
+    reduce::{bigint_to_basefield, bigint_to_limbs, limbs_to_bigint, Reducer},
+    AllocatedMulResultVar, NonNativeFieldConfig,
 };
 use crate::{convert::ToConstraintFieldGadget, fields::fp::FpVar, prelude::*};
 use ark_ff::{BigInteger, PrimeField};
 use ark_relations::{
-    gr1cs::{
-        ConstraintSystemRef, Namespace, OptimizationGoal, Result as R1CSResult, SynthesisError,
-    },
+    gr1cs::{ConstraintSystemRef, Namespace, Result as R1CSResult, SynthesisError},
     ns,
 };
 use ark_std::{
@@ -34,16 +33,38 @@ pub struct AllocatedEmulatedFpVar<TargetF: PrimeField, BaseF: PrimeField> {
     /// specified in the parameters, and the representation is strictly within
     /// the range of TargetF).
     pub is_in_the_normal_form: bool,
+    /// The limb layout. Only variables with the same layout can be combined.
+    pub params: NonNativeFieldConfig,
     #[doc(hidden)]
     pub target_phantom: PhantomData<TargetF>,
 }
 
+/// The optimization type that matches the optimization goal of `cs`.
+fn optimization_type<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> OptimizationType {
+    cs.optimization_goal().into()
+}
+
 impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, BaseF> {
     /// Return cs
     pub fn cs(&self) -> ConstraintSystemRef<BaseF> {
         self.cs.clone()
     }
 
+    /// The limb layout that is used for new variables in `cs` unless one is
+    /// given explicitly. It depends on the optimization goal of `cs`.
+    pub fn default_params(cs: &ConstraintSystemRef<BaseF>) -> NonNativeFieldConfig {
+        params_for_goal::<TargetF, BaseF>(cs.optimization_goal())
+    }
+
+    /// Panics if `self` and `other` have different limb layouts, as their
+    /// limbs cannot be combined.
+    pub(crate) fn assert_same_params(&self, other: &Self) {
+        assert_eq!(
+            self.params, other.params,
+            "cannot combine emulated field variables with different limb layouts"
+        );
+    }
+
     /// Obtain the value of limbs
     pub fn limbs_to_value(limbs: Vec<BaseF>, optimization_type: OptimizationType) -> TargetF {
         let params = get_params(
@@ -51,7 +72,11 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             BaseF::MODULUS_BIT_SIZE as usize,
             optimization_type,
         );
+        Self::limbs_to_value_with_params(limbs, &params)
+    }
 
+    /// Obtain the value of limbs in the layout given by `params`
+    pub fn limbs_to_value_with_params(limbs: Vec<BaseF>, params: &NonNativeFieldConfig) -> TargetF {
         // Convert 2^{(params.bits_per_limb - 1)} into the TargetF and then double
         // the base This is because 2^{(params.bits_per_limb)} might indeed be
         // larger than the target field's prime.
@@ -88,18 +113,23 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             limbs.push(limb.value()?);
         }
 
-        Ok(Self::limbs_to_value(limbs, self.get_optimization_type()))
+        Ok(Self::limbs_to_value_with_params(limbs, &self.params))
     }
 
     /// Obtain the emulated field element of a constant value
     pub fn constant(cs: ConstraintSystemRef<BaseF>, value: TargetF) -> R1CSResult<Self> {
-        let optimization_type = match cs.optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
-        };
+        let params = Self::default_params(&cs);
+        Self::constant_with_params(cs, value, params)
+    }
 
-        let limbs_value = Self::get_limbs_representations(&value, optimization_type)?;
+    /// Obtain the emulated field element of a constant value, in the layout
+    /// given by `params`
+    pub fn constant_with_params(
+        cs: ConstraintSystemRef<BaseF>,
+        value: TargetF,
+        params: NonNativeFieldConfig,
+    ) -> R1CSResult<Self> {
+        let limbs_value = Self::get_limbs_representations_with_params(&value, &params)?;
 
         let mut limbs = Vec::new();
 
@@ -112,6 +142,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             limbs,
             num_of_additions_over_normal_form: BaseF::zero(),
             is_in_the_normal_form: true,
+            params,
             target_phantom: PhantomData,
         })
     }
@@ -129,7 +160,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     /// Add a emulated field element
     #[tracing::instrument(target = "gr1cs")]
     pub fn add(&self, other: &Self) -> R1CSResult<Self> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
         let mut limbs = Vec::new();
         for (this_limb, other_limb) in self.limbs.iter().zip(other.limbs.iter()) {
@@ -144,6 +175,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
                 .add(&other.num_of_additions_over_normal_form)
                 .add(&BaseF::one()),
             is_in_the_normal_form: false,
+            params: self.params,
             target_phantom: PhantomData,
         };
 
@@ -154,7 +186,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     /// Add a constant
     #[tracing::instrument(target = "gr1cs")]
     pub fn add_constant(&self, other: &TargetF) -> R1CSResult<Self> {
-        let other_limbs = Self::get_limbs_representations(other, self.get_optimization_type())?;
+        let other_limbs = Self::get_limbs_representations_with_params(other, &self.params)?;
 
         let mut limbs = Vec::new();
         for (this_limb, other_limb) in self.limbs.iter().zip(other_limbs.iter()) {
@@ -168,6 +200,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
                 .num_of_additions_over_normal_form
                 .add(&BaseF::one()),
             is_in_the_normal_form: false,
+            params: self.params,
             target_phantom: PhantomData,
         };
 
@@ -179,13 +212,9 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     /// Subtract a emulated field element, without the final reduction step
     #[tracing::instrument(target = "gr1cs")]
     pub fn sub_without_reduce(&self, other: &Self) -> R1CSResult<Self> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+        let params = self.params;
 
         // Step 1: reduce the `other` if needed
         let mut surfeit = overhead!(other.num_of_additions_over_normal_form + BaseF::one()) + 1;
@@ -218,9 +247,8 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         }
 
         // Step 3: prepare to pad the padding to k * p for some k
-        let pad_to_kp_gap = Self::limbs_to_value(pad_limbs, self.get_optimization_type()).neg();
-        let pad_to_kp_limbs =
-            Self::get_limbs_representations(&pad_to_kp_gap, self.get_optimization_type())?;
+        let pad_to_kp_gap = Self::limbs_to_value_with_params(pad_limbs, &params).neg();
+        let pad_to_kp_limbs = Self::get_limbs_representations_with_params(&pad_to_kp_gap, &params)?;
 
         // Step 4: the result is self + pad + pad_to_kp - other
         let mut limbs = Vec::with_capacity(self.limbs.len());
@@ -245,6 +273,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
                 + (other.num_of_additions_over_normal_form + BaseF::one())
                 + (other.num_of_additions_over_normal_form + BaseF::one()),
             is_in_the_normal_form: false,
+            params,
             target_phantom: PhantomData,
         };
 
@@ -254,7 +283,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     /// Subtract a emulated field element
     #[tracing::instrument(target = "gr1cs")]
     pub fn sub(&self, other: &Self) -> R1CSResult<Self> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
         let mut result = self.sub_without_reduce(other)?;
         Reducer::<TargetF, BaseF>::post_add_reduce(&mut result)?;
@@ -264,37 +293,41 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     /// Subtract a constant
     #[tracing::instrument(target = "gr1cs")]
     pub fn sub_constant(&self, other: &TargetF) -> R1CSResult<Self> {
-        self.sub(&Self::constant(self.cs(), *other)?)
+        self.sub(&Self::constant_with_params(self.cs(), *other, self.params)?)
     }
 
     /// Multiply a emulated field element
     #[tracing::instrument(target = "gr1cs")]
     pub fn mul(&self, other: &Self) -> R1CSResult<Self> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
         self.mul_without_reduce(&other)?.reduce()
     }
 
     /// Multiply a constant
     pub fn mul_constant(&self, other: &TargetF) -> R1CSResult<Self> {
-        self.mul(&Self::constant(self.cs(), *other)?)
+        self.mul(&Self::constant_with_params(self.cs(), *other, self.params)?)
     }
 
     /// Compute the negate of a emulated field element
     #[tracing::instrument(target = "gr1cs")]
     pub fn negate(&self) -> R1CSResult<Self> {
-        Self::zero(self.cs())?.sub(self)
+        Self::constant_with_params(self.cs(), TargetF::zero(), self.params)?.sub(self)
     }
 
     /// Compute the inverse of a emulated field element
     #[tracing::instrument(target = "gr1cs")]
     pub fn inverse(&self) -> R1CSResult<Self> {
-        let inverse = Self::new_witness(self.cs(), || {
-            Ok(self.value()?.inverse().unwrap_or_else(TargetF::zero))
-        })?;
+        let inverse = Self::new_variable_with_params(
+            self.cs(),
+            || Ok(self.value()?.inverse().unwrap_or_else(TargetF::zero)),
+            AllocationMode::Witness,
+            self.params,
+        )?;
 
+        let one = Self::constant_with_params(self.cs(), TargetF::one(), self.params)?;
         let actual_result = self.clone().mul(&inverse)?;
-        actual_result.conditional_enforce_equal(&Self::one(self.cs())?, &Boolean::TRUE)?;
+        actual_result.conditional_enforce_equal(&one, &Boolean::TRUE)?;
         Ok(inverse)
     }
 
@@ -308,6 +341,15 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         Self::get_limbs_representations_from_big_integer(&elem.into_bigint(), optimization_type)
     }
 
+    /// Convert a `TargetF` element into limbs in the layout given by
+    /// `params` (not constraints).
+    pub fn get_limbs_representations_with_params(
+        elem: &TargetF,
+        params: &NonNativeFieldConfig,
+    ) -> R1CSResult<Vec<BaseF>> {
+        Self::get_limbs_representations_from_big_integer_with_params(&elem.into_bigint(), params)
+    }
+
     /// Obtain the limbs directly from a big int
     pub fn get_limbs_representations_from_big_integer(
         elem: &<TargetF as PrimeField>::BigInt,
@@ -318,23 +360,16 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             BaseF::MODULUS_BIT_SIZE as usize,
             optimization_type,
         );
+        Self::get_limbs_representations_from_big_integer_with_params(elem, &params)
+    }
 
-        // push the lower limbs first
-        let mut limbs: Vec<BaseF> = Vec::new();
-        let mut cur = *elem;
-        for _ in 0..params.num_limbs {
-            let cur_bits = cur.to_bits_be(); // `to_bits` is big endian
-            let cur_mod_r = <BaseF as PrimeField>::BigInt::from_bits_be(
-                &cur_bits[cur_bits.len() - params.bits_per_limb..],
-            ); // therefore, the lowest `bits_per_non_top_limb` bits is what we want.
-            limbs.push(BaseF::from_bigint(cur_mod_r).unwrap());
-            cur >>= params.bits_per_limb as u32;
-        }
-
-        // then we reserve, so that the limbs are ``big limb first''
-        limbs.reverse();
-
-        Ok(limbs)
+    /// Obtain the limbs directly from a big int, in the layout given by
+    /// `params`
+    pub fn get_limbs_representations_from_big_integer_with_params(
+        elem: &<TargetF as PrimeField>::BigInt,
+        params: &NonNativeFieldConfig,
+    ) -> R1CSResult<Vec<BaseF>> {
+        Ok(bigint_to_limbs::<TargetF, BaseF>(elem, params))
     }
 
     /// for advanced use, multiply and output the intermediate representations
@@ -346,13 +381,9 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         &self,
         other: &Self,
     ) -> R1CSResult<AllocatedMulResultVar<TargetF, BaseF>> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+        let params = self.params;
 
         // Step 1: reduce `self` and `other` if neceessary
         let mut self_reduced = self.clone();
@@ -427,6 +458,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             prod_of_num_of_additions: (self_reduced.num_of_additions_over_normal_form
                 + BaseF::one())
                 * (other_reduced.num_of_additions_over_normal_form + BaseF::one()),
+            params,
             target_phantom: PhantomData,
         })
     }
@@ -440,18 +472,14 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         other: &Self,
         should_enforce: &Boolean<BaseF>,
     ) -> R1CSResult<()> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+        let params = self.params;
 
         // Get p
-        let p_representations = Self::get_limbs_representations_from_big_integer(
+        let p_representations = Self::get_limbs_representations_from_big_integer_with_params(
             &<TargetF as PrimeField>::MODULUS,
-            self.get_optimization_type(),
+            &params,
         )?;
         let p_bigint = limbs_to_bigint(params.bits_per_limb, &p_representations);
 
@@ -464,13 +492,15 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             limbs: p_gadget_limbs,
             num_of_additions_over_normal_form: BaseF::one(),
             is_in_the_normal_form: false,
+            params,
             target_phantom: PhantomData,
         };
 
         // Get delta = self - other
         let cs = self.cs().or(other.cs()).or(should_enforce.cs());
         let delta = self.sub_without_reduce(other)?;
-        let delta = should_enforce.select(&delta, &Self::zero(cs.clone())?)?;
+        let zero = Self::constant_with_params(cs.clone(), TargetF::zero(), params)?;
+        let delta = should_enforce.select(&delta, &zero)?;
 
         // Allocate k = delta / p
         let k_gadget = FpVar::<BaseF>::new_witness(ns!(cs, "k"), || {
@@ -485,7 +515,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         })?;
 
         let surfeit = overhead!(delta.num_of_additions_over_normal_form + BaseF::one()) + 1;
-        Reducer::<TargetF, BaseF>::limb_to_bits(&k_gadget, surfeit)?;
+        k_gadget.enforce_bit_length(surfeit)?;
 
         // Compute k * p
         let mut kp_gadget_limbs = Vec::new();
@@ -511,23 +541,43 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         other: &Self,
         should_enforce: &Boolean<BaseF>,
     ) -> R1CSResult<()> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        self.assert_same_params(other);
 
         let cs = self.cs().or(other.cs()).or(should_enforce.cs());
+        let one = Self::constant_with_params(cs, TargetF::one(), self.params)?;
 
-        let _ = should_enforce
-            .select(&self.sub(other)?, &Self::one(cs)?)?
-            .inverse()?;
+        let _ = should_enforce.select(&self.sub(other)?, &one)?.inverse()?;
 
         Ok(())
     }
 
     pub(crate) fn get_optimization_type(&self) -> OptimizationType {
-        match self.cs().optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
+        optimization_type(&self.cs())
+    }
+
+    /// Allocates a new variable in the limb layout given by `params`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` cannot be used to emulate `TargetF` over `BaseF`;
+    /// see [`NonNativeFieldConfig::is_valid_for`].
+    pub fn new_variable_with_params<T: Borrow<TargetF>>(
+        cs: impl Into<Namespace<BaseF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+        params: NonNativeFieldConfig,
+    ) -> R1CSResult<Self> {
+        assert!(
+            params.is_valid_for::<TargetF, BaseF>(),
+            "invalid limb layout for the emulated field"
+        );
+        let ns = cs.into();
+        let cs = ns.cs();
+        let this = Self::new_variable_unchecked(ns!(cs, "alloc"), f, mode, params)?;
+        if mode == AllocationMode::Witness {
+            this.enforce_in_range()?;
         }
+        Ok(this)
     }
 
     /// Allocates a new variable, but does not check that the allocation's limbs
@@ -536,23 +586,18 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         cs: impl Into<Namespace<BaseF>>,
         f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: AllocationMode,
+        params: NonNativeFieldConfig,
     ) -> R1CSResult<Self> {
         let ns = cs.into();
         let cs = ns.cs();
 
-        let optimization_type = match cs.optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
-        };
-
         let zero = TargetF::zero();
 
         let elem = match f() {
             Ok(t) => *(t.borrow()),
             Err(_) => zero,
         };
-        let elem_representations = Self::get_limbs_representations(&elem, optimization_type)?;
+        let elem_representations = Self::get_limbs_representations_with_params(&elem, &params)?;
         let mut limbs = Vec::new();
 
         for limb in elem_representations.iter() {
@@ -574,6 +619,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             limbs,
             num_of_additions_over_normal_form,
             is_in_the_normal_form: mode != AllocationMode::Witness,
+            params,
             target_phantom: PhantomData,
         })
     }
@@ -582,19 +628,8 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     /// the whole number is less than the modulus.
     ///
     /// Returns the bits of the element, in little-endian form
-    fn enforce_in_range(&self, cs: impl Into<Namespace<BaseF>>) -> R1CSResult<Vec<Boolean<BaseF>>> {
-        let ns = cs.into();
-        let cs = ns.cs();
-        let optimization_type = match cs.optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
-        };
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            optimization_type,
-        );
+    fn enforce_in_range(&self) -> R1CSResult<Vec<Boolean<BaseF>>> {
+        let params = self.params;
         let mut bits = Vec::new();
         for limb in self.limbs.iter().rev().take(params.num_limbs - 1) {
             bits.extend(
@@ -626,31 +661,38 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
     ) -> R1CSResult<(Self, Vec<Boolean<BaseF>>)> {
         let ns = cs.into();
         let cs = ns.cs();
-        let this = Self::new_variable_unchecked(ns!(cs, "alloc"), f, AllocationMode::Witness)?;
-        let bits = this.enforce_in_range(ns!(cs, "bits"))?;
+        let params = Self::default_params(&cs);
+        let this =
+            Self::new_variable_unchecked(ns!(cs, "alloc"), f, AllocationMode::Witness, params)?;
+        let bits = this.enforce_in_range()?;
         Ok((this, bits))
     }
-}
 
-impl<TargetF: PrimeField, BaseF: PrimeField> ToBitsGadget<BaseF>
-    for AllocatedEmulatedFpVar<TargetF, BaseF>
-{
+    /// Enforces that the limbs of `self` encode the canonical representative
+    /// of its value, i.e. an integer in `[0, TargetF::MODULUS)`, reducing
+    /// `self` first if necessary.
+    ///
+    /// Afterwards, the limbs can be used as the unique representation of the
+    /// value, e.g. for range conditions on scalars, and `self` does not need
+    /// to be reduced before the next operation.
     #[tracing::instrument(target = "gr1cs")]
-    fn to_bits_le(&self) -> R1CSResult<Vec<Boolean<BaseF>>> {
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+    pub fn enforce_canonical(&mut self) -> R1CSResult<()> {
+        self.canonicalize().map(|_| ())
+    }
+
+    /// Reduces `self` to the canonical representation as in
+    /// [`Self::enforce_canonical`] and returns its `TargetF::MODULUS_BIT_SIZE`
+    /// bits in little-endian order.
+    fn canonicalize(&mut self) -> R1CSResult<Vec<Boolean<BaseF>>> {
+        let params = self.params;
 
         // Reduce to the normal form
         // Though, a malicious prover can make it slightly larger than p
-        let mut self_normal = self.clone();
-        Reducer::<TargetF, BaseF>::pre_eq_reduce(&mut self_normal)?;
+        Reducer::<TargetF, BaseF>::pre_eq_reduce(self)?;
 
         // Therefore, we convert it to bits and enforce that it is in the field
         let mut bits = Vec::<Boolean<BaseF>>::new();
-        for limb in self_normal.limbs.iter() {
+        for limb in self.limbs.iter() {
             bits.extend_from_slice(&Reducer::<TargetF, BaseF>::limb_to_bits(
                 &limb,
                 params.bits_per_limb,
@@ -668,8 +710,166 @@ impl<TargetF: PrimeField, BaseF: PrimeField> ToBitsGadget<BaseF>
         // be empty.
         assert!(run.is_empty());
 
+        // The bits above `TargetF::MODULUS_BIT_SIZE` are enforced to be zero
+        // by the check above, so they are dropped.
+        bits.truncate(TargetF::MODULUS_BIT_SIZE as usize);
+
+        self.num_of_additions_over_normal_form = BaseF::zero();
+        self.is_in_the_normal_form = true;
         Ok(bits)
     }
+
+    /// Allocates a new public input with value given by the function `f`,
+    /// exposing it as `ceil(TargetF::MODULUS_BIT_SIZE / (BaseF::MODULUS_BIT_SIZE - 1))`
+    /// packed field elements instead of one input per limb. The verifier
+    /// computes these with [`to_packed_public_input`].
+    ///
+    /// The packed inputs are decomposed into bits in-circuit, which range
+    /// checks them, and the bits are then regrouped into limbs.
+    pub fn new_packed_input<T: Borrow<TargetF>>(
+        cs: impl Into<Namespace<BaseF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+    ) -> R1CSResult<Self> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let params = Self::default_params(&cs);
+        Self::new_packed_input_with_params(cs, f, params)
+    }
+
+    /// Same as [`Self::new_packed_input`], but the limbs follow `params`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` cannot be used to emulate `TargetF` over `BaseF`;
+    /// see [`NonNativeFieldConfig::is_valid_for`].
+    pub fn new_packed_input_with_params<T: Borrow<TargetF>>(
+        cs: impl Into<Namespace<BaseF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        params: NonNativeFieldConfig,
+    ) -> R1CSResult<Self> {
+        assert!(
+            params.is_valid_for::<TargetF, BaseF>(),
+            "invalid limb layout for the emulated field"
+        );
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        let packed = f().map(|v| to_packed_public_input::<TargetF, BaseF>(v.borrow()));
+        let num_bits = TargetF::MODULUS_BIT_SIZE as usize;
+        let chunk_size = packed_chunk_size::<BaseF>();
+
+        let mut bits = Vec::with_capacity(num_bits);
+        for (i, start) in (0..num_bits).step_by(chunk_size).enumerate() {
+            let input = FpVar::new_input(ns!(cs, "packed input"), || {
+                packed
+                    .as_ref()
+                    .map(|p| p[i])
+                    .map_err(|_| SynthesisError::AssignmentMissing)
+            })?;
+            bits.extend(input.to_bits_le_with_bit_length(min(chunk_size, num_bits - start))?);
+        }
+
+        let mut limbs = bits
+            .chunks(params.bits_per_limb)
+            .map(Boolean::le_bits_to_fp)
+            .collect::<R1CSResult<Vec<_>>>()?;
+        // the limbs are ``big limb first''
+        limbs.reverse();
+
+        // Like a witness, the value is only known to be below
+        // `2^TargetF::MODULUS_BIT_SIZE`.
+        Ok(Self {
+            cs,
+            limbs,
+            num_of_additions_over_normal_form: BaseF::one(),
+            is_in_the_normal_form: false,
+            params,
+            target_phantom: PhantomData,
+        })
+    }
+
+    /// Builds the element whose value is the integer with little-endian bits
+    /// `bits`, reduced modulo `TargetF::MODULUS`, in the limb layout
+    /// `params`.
+    ///
+    /// The bits are regrouped into limbs without further constraints; the
+    /// most significant limb absorbs the bits beyond
+    /// `TargetF::MODULUS_BIT_SIZE`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` cannot be used to emulate `TargetF` over `BaseF`,
+    /// or if the most significant limb would not fit into `BaseF`.
+    pub fn from_fpvar_bits(
+        bits: &[Boolean<BaseF>],
+        params: NonNativeFieldConfig,
+    ) -> R1CSResult<Self> {
+        assert!(
+            params.is_valid_for::<TargetF, BaseF>(),
+            "invalid limb layout for the emulated field"
+        );
+        let num_bits = bits.len();
+        let top_limb_start = (params.num_limbs - 1) * params.bits_per_limb;
+        assert!(
+            num_bits.saturating_sub(top_limb_start) < BaseF::MODULUS_BIT_SIZE as usize,
+            "too many bits for the limb layout"
+        );
+
+        let mut limbs = Vec::with_capacity(params.num_limbs);
+        for i in 0..params.num_limbs {
+            let start = min(i * params.bits_per_limb, num_bits);
+            let end = if i == params.num_limbs - 1 {
+                num_bits
+            } else {
+                min(start + params.bits_per_limb, num_bits)
+            };
+            limbs.push(Boolean::le_bits_to_fp(&bits[start..end])?);
+        }
+        // the limbs are ``big limb first''
+        limbs.reverse();
+
+        // Below `2^(TargetF::MODULUS_BIT_SIZE - 1)` the value is smaller than
+        // the modulus. Otherwise it is below `2^(num_bits - TargetF::MODULUS_BIT_SIZE + 1)`
+        // times the modulus.
+        let target_bits = TargetF::MODULUS_BIT_SIZE as usize;
+        let (num_of_additions_over_normal_form, is_in_the_normal_form) = if num_bits < target_bits {
+            (BaseF::zero(), true)
+        } else {
+            (
+                BaseF::from(2u64).pow([(num_bits - target_bits + 1) as u64]) - BaseF::one(),
+                false,
+            )
+        };
+
+        Ok(Self {
+            cs: bits.cs(),
+            limbs,
+            num_of_additions_over_normal_form,
+            is_in_the_normal_form,
+            params,
+            target_phantom: PhantomData,
+        })
+    }
+
+    /// Converts `self` into a native `FpVar`, enforcing that its value, as an
+    /// integer in `[0, TargetF::MODULUS)`, is smaller than `BaseF::MODULUS`.
+    ///
+    /// The constraint system is unsatisfiable if the value does not fit.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn to_fpvar(&self) -> R1CSResult<FpVar<BaseF>> {
+        // `le_bits_to_fp` enforces that the canonical bits are in `BaseF` if
+        // there are enough of them to exceed its modulus.
+        Boolean::le_bits_to_fp(&self.to_bits_le()?)
+    }
+}
+
+impl<TargetF: PrimeField, BaseF: PrimeField> ToBitsGadget<BaseF>
+    for AllocatedEmulatedFpVar<TargetF, BaseF>
+{
+    #[tracing::instrument(target = "gr1cs")]
+    fn to_bits_le(&self) -> R1CSResult<Vec<Boolean<BaseF>>> {
+        self.clone().canonicalize()
+    }
 }
 
 impl<TargetF: PrimeField, BaseF: PrimeField> ToBytesGadget<BaseF>
@@ -697,10 +897,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> CondSelectGadget<BaseF>
         true_value: &Self,
         false_value: &Self,
     ) -> R1CSResult<Self> {
-        assert_eq!(
-            true_value.get_optimization_type(),
-            false_value.get_optimization_type()
-        );
+        true_value.assert_same_params(false_value);
 
         let mut limbs_sel = Vec::with_capacity(true_value.limbs.len());
 
@@ -717,6 +914,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> CondSelectGadget<BaseF>
             ),
             is_in_the_normal_form: true_value.is_in_the_normal_form
                 && false_value.is_in_the_normal_form,
+            params: true_value.params,
             target_phantom: PhantomData,
         })
     }
@@ -737,28 +935,14 @@ impl<TargetF: PrimeField, BaseF: PrimeField> TwoBitLookupGadget<BaseF>
 
         let cs = bits.cs();
 
-        let optimization_type = match cs.optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
-        };
-
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            optimization_type,
-        );
+        let params = Self::default_params(&cs);
         let mut limbs_constants = Vec::new();
         for _ in 0..params.num_limbs {
             limbs_constants.push(Vec::new());
         }
 
         for constant in constants.iter() {
-            let representations =
-                AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations(
-                    constant,
-                    optimization_type,
-                )?;
+            let representations = Self::get_limbs_representations_with_params(constant, &params)?;
 
             for (i, representation) in representations.iter().enumerate() {
                 limbs_constants[i].push(*representation);
@@ -775,6 +959,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> TwoBitLookupGadget<BaseF>
             limbs,
             num_of_additions_over_normal_form: BaseF::zero(),
             is_in_the_normal_form: true,
+            params,
             target_phantom: PhantomData,
         })
     }
@@ -796,17 +981,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> ThreeBitCondNegLookupGadget<BaseF>
 
         let cs = bits.cs().or(b0b1.cs());
 
-        let optimization_type = match cs.optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
-        };
-
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            optimization_type,
-        );
+        let params = Self::default_params(&cs);
 
         let mut limbs_constants = Vec::new();
         for _ in 0..params.num_limbs {
@@ -814,11 +989,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> ThreeBitCondNegLookupGadget<BaseF>
         }
 
         for constant in constants.iter() {
-            let representations =
-                AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations(
-                    constant,
-                    optimization_type,
-                )?;
+            let representations = Self::get_limbs_representations_with_params(constant, &params)?;
 
             for (i, representation) in representations.iter().enumerate() {
                 limbs_constants[i].push(*representation);
@@ -839,6 +1010,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> ThreeBitCondNegLookupGadget<BaseF>
             limbs,
             num_of_additions_over_normal_form: BaseF::zero(),
             is_in_the_normal_form: true,
+            params,
             target_phantom: PhantomData,
         })
     }
@@ -854,11 +1026,8 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocVar<TargetF, BaseF>
     ) -> R1CSResult<Self> {
         let ns = cs.into();
         let cs = ns.cs();
-        let this = Self::new_variable_unchecked(ns!(cs, "alloc"), f, mode)?;
-        if mode == AllocationMode::Witness {
-            this.enforce_in_range(ns!(cs, "bits"))?;
-        }
-        Ok(this)
+        let params = Self::default_params(&cs);
+        Self::new_variable_with_params(cs, f, mode, params)
     }
 }
 
@@ -907,6 +1076,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> Clone for AllocatedEmulatedFpVar<Ta
             limbs: self.limbs.clone(),
             num_of_additions_over_normal_form: self.num_of_additions_over_normal_form,
             is_in_the_normal_form: self.is_in_the_normal_form,
+            params: self.params,
             target_phantom: PhantomData,
         }
     }
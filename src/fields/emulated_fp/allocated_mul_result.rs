@@ -1,12 +1,11 @@
 use super::{
-    params::{get_params, OptimizationType},
-    reduce::{bigint_to_basefield, limbs_to_bigint, Reducer},
-    AllocatedEmulatedFpVar,
+    reduce::{bigint_to_basefield, limbs_to_bigint, overhead_of_product, Reducer},
+    AllocatedEmulatedFpVar, NonNativeFieldConfig,
 };
 use crate::{fields::fp::FpVar, prelude::*};
 use ark_ff::PrimeField;
 use ark_relations::{
-    gr1cs::{ConstraintSystemRef, OptimizationGoal, Result as R1CSResult},
+    gr1cs::{ConstraintSystemRef, Result as R1CSResult},
     ns,
 };
 use ark_std::{marker::PhantomData, vec::Vec};
@@ -22,6 +21,8 @@ pub struct AllocatedMulResultVar<TargetF: PrimeField, BaseF: PrimeField> {
     pub limbs: Vec<FpVar<BaseF>>,
     /// The cumulative num of additions
     pub prod_of_num_of_additions: BaseF,
+    /// The limb layout of the multiplied elements
+    pub params: NonNativeFieldConfig,
     #[doc(hidden)]
     pub target_phantom: PhantomData<TargetF>,
 }
@@ -30,11 +31,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> From<&AllocatedEmulatedFpVar<Target
     for AllocatedMulResultVar<TargetF, BaseF>
 {
     fn from(src: &AllocatedEmulatedFpVar<TargetF, BaseF>) -> Self {
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            src.get_optimization_type(),
-        );
+        let params = src.params;
 
         let mut limbs = src.limbs.clone();
         limbs.reverse();
@@ -47,6 +44,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> From<&AllocatedEmulatedFpVar<Target
             cs: src.cs(),
             limbs,
             prod_of_num_of_additions,
+            params,
             target_phantom: PhantomData,
         }
     }
@@ -60,16 +58,12 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
 
     /// Get the value of the multiplication result
     pub fn value(&self) -> R1CSResult<TargetF> {
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+        let params = self.params;
 
         let p_representations =
-            AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations_from_big_integer(
+            AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations_from_big_integer_with_params(
                 &<TargetF as PrimeField>::MODULUS,
-                self.get_optimization_type(),
+                &params,
             )?;
         let p_bigint = limbs_to_bigint(params.bits_per_limb, &p_representations);
 
@@ -86,17 +80,23 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
     /// Constraints for reducing the result of a multiplication mod p, to get an
     /// original representation.
     pub fn reduce(&self) -> R1CSResult<AllocatedEmulatedFpVar<TargetF, BaseF>> {
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+        let params = self.params;
+
+        // A single limb is reduced with one quotient of as many bits as `k`
+        let surfeit = overhead!(self.prod_of_num_of_additions + BaseF::one()) + 1 + 1;
+        if let Some(r) = Reducer::<TargetF, BaseF>::reduce_single_limb(
+            &self.limbs[0],
+            TargetF::MODULUS_BIT_SIZE as usize + surfeit,
+            params,
+        )? {
+            return Ok(r);
+        }
 
         // Step 1: get p
         let p_representations =
-            AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations_from_big_integer(
+            AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations_from_big_integer_with_params(
                 &<TargetF as PrimeField>::MODULUS,
-                self.get_optimization_type(),
+                &params,
             )?;
         let p_bigint = limbs_to_bigint(params.bits_per_limb, &p_representations);
 
@@ -109,13 +109,11 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
             limbs: p_gadget_limbs,
             num_of_additions_over_normal_form: BaseF::one(),
             is_in_the_normal_form: false,
+            params,
             target_phantom: PhantomData,
         };
 
-        // Step 2: compute surfeit
-        let surfeit = overhead!(self.prod_of_num_of_additions + BaseF::one()) + 1 + 1;
-
-        // Step 3: allocate k
+        // Step 2: allocate k
         let k_bits = {
             let mut res = Vec::new();
 
@@ -173,20 +171,18 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
             limbs: k_limbs,
             num_of_additions_over_normal_form: self.prod_of_num_of_additions,
             is_in_the_normal_form: false,
+            params,
             target_phantom: PhantomData,
         };
 
         let cs = self.cs();
 
-        let r_gadget = AllocatedEmulatedFpVar::<TargetF, BaseF>::new_witness(ns!(cs, "r"), || {
-            Ok(self.value()?)
-        })?;
-
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            self.get_optimization_type(),
-        );
+        let r_gadget = AllocatedEmulatedFpVar::<TargetF, BaseF>::new_variable_with_params(
+            ns!(cs, "r"),
+            || Ok(self.value()?),
+            AllocationMode::Witness,
+            params,
+        )?;
 
         // Step 1: reduce `self` and `other` if neceessary
         let mut prod_limbs = Vec::new();
@@ -207,6 +203,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
             limbs: prod_limbs,
             prod_of_num_of_additions: (p_gadget.num_of_additions_over_normal_form + BaseF::one())
                 * (k_gadget.num_of_additions_over_normal_form + BaseF::one()),
+            params,
             target_phantom: PhantomData,
         };
 
@@ -229,7 +226,10 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
     /// Add unreduced elements.
     #[tracing::instrument(target = "gr1cs")]
     pub fn add(&self, other: &Self) -> R1CSResult<Self> {
-        assert_eq!(self.get_optimization_type(), other.get_optimization_type());
+        assert_eq!(
+            self.params, other.params,
+            "cannot combine emulated field variables with different limb layouts"
+        );
 
         let mut new_limbs = Vec::new();
 
@@ -243,6 +243,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
             limbs: new_limbs,
             prod_of_num_of_additions: self.prod_of_num_of_additions
                 + other.prod_of_num_of_additions,
+            params: self.params,
             target_phantom: PhantomData,
         })
     }
@@ -250,10 +251,11 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
     /// Add native constant elem
     #[tracing::instrument(target = "gr1cs")]
     pub fn add_constant(&self, other: &TargetF) -> R1CSResult<Self> {
-        let mut other_limbs = AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations(
-            other,
-            self.get_optimization_type(),
-        )?;
+        let mut other_limbs =
+            AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations_with_params(
+                other,
+                &self.params,
+            )?;
         other_limbs.reverse();
 
         let mut new_limbs = Vec::new();
@@ -272,15 +274,53 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedMulResultVar<TargetF, Base
             cs: self.cs(),
             limbs: new_limbs,
             prod_of_num_of_additions: self.prod_of_num_of_additions + BaseF::one(),
+            params: self.params,
             target_phantom: PhantomData,
         })
     }
 
-    pub(crate) fn get_optimization_type(&self) -> OptimizationType {
-        match self.cs().optimization_goal() {
-            OptimizationGoal::None => OptimizationType::Constraints,
-            OptimizationGoal::Constraints => OptimizationType::Constraints,
-            OptimizationGoal::Weight => OptimizationType::Weight,
+    /// Add unreduced elements, first reducing `self` (and, if that is not
+    /// enough, `other`) if the limbs of the sum could no longer be reduced
+    /// without overflowing `BaseF`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn add_lazily(&self, other: &Self) -> R1CSResult<Self> {
+        let fits = |a: &Self, b: &Self| {
+            Self::has_headroom(
+                &a.params,
+                a.prod_of_num_of_additions + b.prod_of_num_of_additions,
+            )
+        };
+        if fits(self, other) {
+            return self.add(other);
+        }
+        let this = Self::from(&self.reduce()?);
+        if fits(&this, other) {
+            return this.add(other);
         }
+        this.add(&Self::from(&other.reduce()?))
+    }
+
+    /// Add native constant elem, first reducing `self` if the limbs of the
+    /// sum could no longer be reduced without overflowing `BaseF`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn add_constant_lazily(&self, other: &TargetF) -> R1CSResult<Self> {
+        if Self::has_headroom(&self.params, self.prod_of_num_of_additions + BaseF::one()) {
+            self.add_constant(other)
+        } else {
+            Self::from(&self.reduce()?).add_constant(other)
+        }
+    }
+
+    /// Whether a sum of products with the cumulative num of additions
+    /// `prod_of_num_of_additions` fits into the limbs, i.e. whether its limbs
+    /// do not wrap around `BaseF` and `reduce` can still check them.
+    fn has_headroom(params: &NonNativeFieldConfig, prod_of_num_of_additions: BaseF) -> bool {
+        let base_bits = BaseF::MODULUS_BIT_SIZE as usize;
+        // Bound on the limbs of the products, as in `Reducer::pre_mul_reduce`
+        2 * (params.bits_per_limb + 1) + overhead_of_product(prod_of_num_of_additions, params.num_limbs)
+            < base_bits
+            // `reduce` must be able to put at least one limb in each group
+            && 2 * params.bits_per_limb + overhead!(prod_of_num_of_additions + BaseF::one()) + 6
+                <= base_bits
     }
 }
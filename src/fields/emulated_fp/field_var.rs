@@ -1,6 +1,10 @@
-use super::{params::OptimizationType, AllocatedEmulatedFpVar, MulResultVar};
+use super::{
+    params::OptimizationType, AllocatedEmulatedFpVar, AllocatedMulResultVar, MulResultVar,
+    NonNativeFieldConfig,
+};
 use crate::{
     boolean::Boolean,
+    cmp::CmpGadget,
     convert::{ToBitsGadget, ToBytesGadget, ToConstraintFieldGadget},
     fields::{fp::FpVar, FieldVar},
     prelude::*,
@@ -13,6 +17,7 @@ use ark_std::{
     hash::{Hash, Hasher},
     vec::Vec,
 };
+use num_bigint::BigUint;
 
 /// A gadget for representing non-native (`TargetF`) field elements over the
 /// constraint field (`BaseF`).
@@ -238,8 +243,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> EqGadget<BaseF> for EmulatedFpVar<T
                 Ok(())
             },
             (Self::Constant(c), Self::Var(v)) | (Self::Var(v), Self::Constant(c)) => {
-                let cs = v.cs();
-                let c = AllocatedEmulatedFpVar::new_constant(cs, c)?;
+                let c = AllocatedEmulatedFpVar::constant_with_params(v.cs(), *c, v.params)?;
                 c.conditional_enforce_equal(v, should_enforce)
             },
             (Self::Var(v1), Self::Var(v2)) => v1.conditional_enforce_equal(v2, should_enforce),
@@ -260,8 +264,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> EqGadget<BaseF> for EmulatedFpVar<T
                 Ok(())
             },
             (Self::Constant(c), Self::Var(v)) | (Self::Var(v), Self::Constant(c)) => {
-                let cs = v.cs();
-                let c = AllocatedEmulatedFpVar::new_constant(cs, c)?;
+                let c = AllocatedEmulatedFpVar::constant_with_params(v.cs(), *c, v.params)?;
                 c.conditional_enforce_not_equal(v, should_enforce)
             },
             (Self::Var(v1), Self::Var(v2)) => v1.conditional_enforce_not_equal(v2, should_enforce),
@@ -291,6 +294,23 @@ impl<TargetF: PrimeField, BaseF: PrimeField> ToBitsGadget<BaseF> for EmulatedFpV
     }
 }
 
+/// Compares `EmulatedFpVar`s as canonical integers in `[0, TargetF::MODULUS)`.
+///
+/// Both operands are decomposed with the canonical
+/// [`ToBitsGadget::to_bits_le`], so a constant operand only costs the
+/// decomposition of the other one.
+impl<TargetF: PrimeField, BaseF: PrimeField> CmpGadget<BaseF> for EmulatedFpVar<TargetF, BaseF> {
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_ge(&self, other: &Self) -> R1CSResult<Boolean<BaseF>> {
+        if let (Self::Constant(a), Self::Constant(b)) = (self, other) {
+            return Ok(Boolean::constant(a >= b));
+        }
+        let self_bits = self.to_bits_le()?;
+        let other_bits = other.to_bits_le()?;
+        FpVar::is_ge_from_bits_le(&self_bits, &other_bits)
+    }
+}
+
 impl<TargetF: PrimeField, BaseF: PrimeField> ToBytesGadget<BaseF>
     for EmulatedFpVar<TargetF, BaseF>
 {
@@ -332,12 +352,22 @@ impl<TargetF: PrimeField, BaseF: PrimeField> CondSelectGadget<BaseF>
             &Boolean::Constant(false) => Ok(false_value.clone()),
             _ => {
                 let cs = cond.cs();
+                // A constant takes the limb layout of the other branch, if
+                // that one is a variable.
+                let params = true_value
+                    .params()
+                    .or(false_value.params())
+                    .unwrap_or_else(|| AllocatedEmulatedFpVar::default_params(&cs));
                 let true_value = match true_value {
-                    Self::Constant(f) => AllocatedEmulatedFpVar::new_constant(cs.clone(), f)?,
+                    Self::Constant(f) => {
+                        AllocatedEmulatedFpVar::constant_with_params(cs.clone(), *f, params)?
+                    },
                     Self::Var(v) => v.clone(),
                 };
                 let false_value = match false_value {
-                    Self::Constant(f) => AllocatedEmulatedFpVar::new_constant(cs, f)?,
+                    Self::Constant(f) => {
+                        AllocatedEmulatedFpVar::constant_with_params(cs, *f, params)?
+                    },
                     Self::Var(v) => v.clone(),
                 };
                 cond.select(&true_value, &false_value).map(Self::Var)
@@ -424,6 +454,134 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocVar<TargetF, BaseF>
     }
 }
 
+impl<TargetF: PrimeField, BaseF: PrimeField> EmulatedFpVar<TargetF, BaseF> {
+    /// Allocates a new variable whose limbs follow `params` instead of the
+    /// layout chosen from the optimization goal of `cs`. Variables can only
+    /// be combined with variables of the same layout; constants adapt to
+    /// the layout of the variable they are combined with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` cannot be used to emulate `TargetF` over `BaseF`;
+    /// see [`NonNativeFieldConfig::is_valid_for`].
+    pub fn new_variable_with_params<T: Borrow<TargetF>>(
+        cs: impl Into<Namespace<BaseF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+        params: NonNativeFieldConfig,
+    ) -> R1CSResult<Self> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        if cs == ConstraintSystemRef::None || mode == AllocationMode::Constant {
+            Ok(Self::Constant(*f()?.borrow()))
+        } else {
+            AllocatedEmulatedFpVar::new_variable_with_params(cs, f, mode, params).map(Self::Var)
+        }
+    }
+
+    /// Allocates a new public input that is exposed as a few packed field
+    /// elements instead of one input per limb; see
+    /// [`AllocatedEmulatedFpVar::new_packed_input`]. The verifier computes
+    /// the inputs with [`to_packed_public_input`](super::to_packed_public_input).
+    pub fn new_packed_input<T: Borrow<TargetF>>(
+        cs: impl Into<Namespace<BaseF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+    ) -> R1CSResult<Self> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        if cs == ConstraintSystemRef::None {
+            Ok(Self::Constant(*f()?.borrow()))
+        } else {
+            AllocatedEmulatedFpVar::new_packed_input(cs, f).map(Self::Var)
+        }
+    }
+
+    /// Enforces that `self` is represented by the canonical representative
+    /// of its value in `[0, TargetF::MODULUS)`; see
+    /// [`AllocatedEmulatedFpVar::enforce_canonical`]. Constants are always
+    /// canonical.
+    pub fn enforce_canonical(&mut self) -> R1CSResult<()> {
+        match self {
+            Self::Constant(_) => Ok(()),
+            Self::Var(v) => v.enforce_canonical(),
+        }
+    }
+
+    /// The limb layout of `self`, or `None` if `self` is a constant.
+    pub fn params(&self) -> Option<NonNativeFieldConfig> {
+        match self {
+            Self::Constant(_) => None,
+            Self::Var(v) => Some(v.params),
+        }
+    }
+
+    /// Builds the element whose value is the integer with little-endian bits
+    /// `bits`, e.g. the bits of a variable of the other field of a cycle,
+    /// reduced modulo `TargetF::MODULUS`; see
+    /// [`AllocatedEmulatedFpVar::from_fpvar_bits`]. The limbs follow the
+    /// default layout of the constraint system of `bits`.
+    pub fn from_fpvar_bits(bits: &[Boolean<BaseF>]) -> R1CSResult<Self> {
+        if bits.is_constant() {
+            let bytes = bits
+                .value()?
+                .chunks(8)
+                .map(|c| c.iter().rev().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+                .collect::<Vec<_>>();
+            return Ok(Self::Constant(TargetF::from_le_bytes_mod_order(&bytes)));
+        }
+
+        let params = AllocatedEmulatedFpVar::<TargetF, BaseF>::default_params(&bits.cs());
+        AllocatedEmulatedFpVar::from_fpvar_bits(bits, params).map(Self::Var)
+    }
+
+    /// Converts the native `x` into an element with the same value, which
+    /// is possible because `TargetF` is larger than `BaseF`.
+    ///
+    /// `x` is decomposed into its canonical bits, which range checks them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `TargetF::MODULUS` is not larger than `BaseF::MODULUS`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn from_fpvar(x: &FpVar<BaseF>) -> R1CSResult<Self> {
+        let base_modulus: BigUint = BaseF::MODULUS.into();
+        let target_modulus: BigUint = TargetF::MODULUS.into();
+        assert!(
+            base_modulus < target_modulus,
+            "the native field does not fit into the emulated field"
+        );
+
+        match x {
+            FpVar::Constant(c) => Ok(Self::Constant(TargetF::from(BigUint::from(*c)))),
+            FpVar::Var(_) => Self::from_fpvar_bits(&x.to_bits_le()?),
+        }
+    }
+
+    /// Converts `self` into a native `FpVar` with the same value; see
+    /// [`AllocatedEmulatedFpVar::to_fpvar`].
+    ///
+    /// Returns an error if `self` is a constant that does not fit into
+    /// `BaseF`; for variables, the constraint system becomes unsatisfiable
+    /// instead.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn to_fpvar(&self) -> R1CSResult<FpVar<BaseF>> {
+        match self {
+            Self::Constant(c) => {
+                let value: BigUint = (*c).into();
+                let base_modulus: BigUint = BaseF::MODULUS.into();
+                if value < base_modulus {
+                    Ok(FpVar::Constant(BaseF::from(value)))
+                } else {
+                    Err(SynthesisError::Unsatisfiable)
+                }
+            },
+            Self::Var(v) => v.to_fpvar(),
+        }
+    }
+}
+
 impl<TargetF: PrimeField, BaseF: PrimeField> ToConstraintFieldGadget<BaseF>
     for EmulatedFpVar<TargetF, BaseF>
 {
@@ -447,6 +605,85 @@ impl<TargetF: PrimeField, BaseF: PrimeField> ToConstraintFieldGadget<BaseF>
 }
 
 impl<TargetF: PrimeField, BaseF: PrimeField> EmulatedFpVar<TargetF, BaseF> {
+    /// Computes `a_1 * b_1 + ... + a_n * b_n` for `terms = [(a_1, b_1), ...,
+    /// (a_n, b_n)]`.
+    ///
+    /// The products are accumulated without reduction, so that the sum is
+    /// usually reduced only once. An intermediate reduction is inserted
+    /// only when the accumulated limbs would otherwise overflow `BaseF`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn sum_of_products(terms: &[(Self, Self)]) -> R1CSResult<Self> {
+        let mut constant = TargetF::zero();
+        let mut sum = None;
+        for (a, b) in terms {
+            Self::accumulate_product(&mut sum, &mut constant, a, b)?;
+        }
+        Self::finish_sum_of_products(sum, constant)
+    }
+
+    /// Computes `s_1 * a_1 * b_1 + ... + s_n * a_n * b_n` for `terms = [(a_1,
+    /// b_1, is_negated_1), ...]`, where `s_i` is `-1` if `is_negated_i` is set
+    /// and `1` otherwise.
+    ///
+    /// As in [`Self::sum_of_products`], the sum is usually reduced only once.
+    /// A negated product costs an additional subtraction, which does not need
+    /// a reduction.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn signed_sum_of_products(terms: &[(Self, Self, bool)]) -> R1CSResult<Self> {
+        let mut constant = TargetF::zero();
+        let mut sum = None;
+        for (a, b, is_negated) in terms {
+            if *is_negated {
+                let b = match b {
+                    Self::Constant(c) => Self::Constant(-*c),
+                    Self::Var(v) => {
+                        let zero = AllocatedEmulatedFpVar::constant_with_params(
+                            v.cs(),
+                            TargetF::zero(),
+                            v.params,
+                        )?;
+                        Self::Var(zero.sub_without_reduce(v)?)
+                    },
+                };
+                Self::accumulate_product(&mut sum, &mut constant, a, &b)?;
+            } else {
+                Self::accumulate_product(&mut sum, &mut constant, a, b)?;
+            }
+        }
+        Self::finish_sum_of_products(sum, constant)
+    }
+
+    /// Adds `a * b` to the accumulator of a sum of products, whose constant
+    /// part is kept separately in `constant`.
+    fn accumulate_product(
+        sum: &mut Option<AllocatedMulResultVar<TargetF, BaseF>>,
+        constant: &mut TargetF,
+        a: &Self,
+        b: &Self,
+    ) -> R1CSResult<()> {
+        match a.mul_without_reduce(b)? {
+            MulResultVar::Constant(c) => *constant += c,
+            MulResultVar::Var(product) => {
+                *sum = Some(match sum.take() {
+                    Some(sum) => sum.add_lazily(&product)?,
+                    None => product,
+                });
+            },
+        }
+        Ok(())
+    }
+
+    fn finish_sum_of_products(
+        sum: Option<AllocatedMulResultVar<TargetF, BaseF>>,
+        constant: TargetF,
+    ) -> R1CSResult<Self> {
+        match sum {
+            None => Ok(Self::Constant(constant)),
+            Some(sum) if constant.is_zero() => sum.reduce().map(Self::Var),
+            Some(sum) => sum.add_constant_lazily(&constant)?.reduce().map(Self::Var),
+        }
+    }
+
     /// The `mul_without_reduce` for `EmulatedFpVar`
     #[tracing::instrument(target = "gr1cs")]
     pub fn mul_without_reduce(&self, other: &Self) -> R1CSResult<MulResultVar<TargetF, BaseF>> {
@@ -454,15 +691,22 @@ impl<TargetF: PrimeField, BaseF: PrimeField> EmulatedFpVar<TargetF, BaseF> {
             Self::Constant(c) => match other {
                 Self::Constant(other_c) => Ok(MulResultVar::Constant(*c * other_c)),
                 Self::Var(other_v) => {
-                    let self_v =
-                        AllocatedEmulatedFpVar::<TargetF, BaseF>::new_constant(self.cs(), c)?;
+                    let self_v = AllocatedEmulatedFpVar::<TargetF, BaseF>::constant_with_params(
+                        other_v.cs(),
+                        *c,
+                        other_v.params,
+                    )?;
                     Ok(MulResultVar::Var(other_v.mul_without_reduce(&self_v)?))
                 },
             },
             Self::Var(v) => {
                 let other_v = match other {
                     Self::Constant(other_c) => {
-                        AllocatedEmulatedFpVar::<TargetF, BaseF>::new_constant(self.cs(), other_c)?
+                        AllocatedEmulatedFpVar::<TargetF, BaseF>::constant_with_params(
+                            v.cs(),
+                            *other_c,
+                            v.params,
+                        )?
                     },
                     Self::Var(other_v) => other_v.clone(),
                 };
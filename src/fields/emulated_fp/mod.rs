@@ -54,6 +54,18 @@
 //! # }
 //! ```
 //!
+//! ## Public inputs
+//!
+//! An [`EmulatedFpVar`] allocated with `new_input` is exposed to the verifier
+//! as its limbs. The verifier can compute the same vector natively with
+//! [`to_public_input`] or [`to_public_inputs`], given the limb layout from
+//! [`params_for_goal`].
+//!
+//! Exposing one input per limb can be costly for the verifier. Allocating
+//! with [`EmulatedFpVar::new_packed_input`] instead exposes the element in as
+//! few `BaseF` elements as its bits fit into, which the verifier computes
+//! with [`to_packed_public_input`].
+//!
 //! ## Advanced optimization
 //!
 //! After each multiplication, our library internally performs a *reduce*
@@ -94,6 +106,19 @@
 //! It performs only one *reduce* operation and is roughly 2x faster than the
 //! first implementation.
 //!
+//! [`EmulatedFpVar::sum_of_products`] and
+//! [`EmulatedFpVar::signed_sum_of_products`] do this for any number of
+//! products, and insert an extra *reduce* only when the accumulated limbs
+//! would otherwise overflow.
+//!
+//! ## Small target fields
+//!
+//! When `TargetF` is much smaller than `BaseF` (e.g. Goldilocks or BabyBear
+//! inside BN254), the default layout has a single limb, and reductions only
+//! need one quotient and one range check. [`PackedEmulatedFpVar`] additionally
+//! packs several such elements into one `BaseF` variable, so that they can be
+//! added with a single addition.
+//!
 //! ## Inspiration and basic design
 //!
 //! This implementation employs the standard idea of using multiple **limbs** to
@@ -108,6 +133,12 @@
 //! After some computation, the limbs become saturated and need to be
 //! **reduced**, in order to engage in more computation.
 //!
+//! The base field may also be much smaller than the target field, e.g. a
+//! 64-bit (Goldilocks) or 31-bit (BabyBear, Mersenne31) field. The limbs are
+//! then only a few bits wide, and [`params::find_parameters`] only considers
+//! limbs for which products can still be reduced in `BaseF`, ranking them
+//! with the estimates of [`params::estimate_costs_with_params`].
+//!
 //! We heavily use the optimization techniques in [\[KPS18\]](https://akosba.github.io/papers/xjsnark.pdf) and [\[OWWB20\]](https://eprint.iacr.org/2019/1494).
 //! Both works have their own open-source libraries:
 //! [xJsnark](https://github.com/akosba/xjsnark) and
@@ -124,6 +155,14 @@
 //!
 //! [`EmulatedFpVar`]: crate::fields::emulated_fp::EmulatedFpVar
 //! [`MulResultVar`]: crate::fields::emulated_fp::MulResultVar
+//! [`to_public_input`]: crate::fields::emulated_fp::to_public_input
+//! [`to_public_inputs`]: crate::fields::emulated_fp::to_public_inputs
+//! [`params_for_goal`]: crate::fields::emulated_fp::params_for_goal
+//! [`EmulatedFpVar::new_packed_input`]: crate::fields::emulated_fp::EmulatedFpVar::new_packed_input
+//! [`EmulatedFpVar::sum_of_products`]: crate::fields::emulated_fp::EmulatedFpVar::sum_of_products
+//! [`EmulatedFpVar::signed_sum_of_products`]: crate::fields::emulated_fp::EmulatedFpVar::signed_sum_of_products
+//! [`to_packed_public_input`]: crate::fields::emulated_fp::to_packed_public_input
+//! [`PackedEmulatedFpVar`]: crate::fields::emulated_fp::PackedEmulatedFpVar
 //! [`FpVar`]: crate::fields::fp::FpVar
 
 #![allow(
@@ -136,7 +175,8 @@
 
 use ark_std::fmt::Debug;
 
-/// Utilities for sampling parameters for non-native field gadgets
+/// Utilities for sampling parameters for non-native field gadgets, and for
+/// estimating their costs
 ///
 /// - `BaseF`:              the constraint field
 /// - `TargetF`:            the field being simulated
@@ -179,7 +219,13 @@ macro_rules! overhead {
 pub(crate) use overhead;
 
 /// Parameters for a specific `EmulatedFpVar` instantiation
-#[derive(Clone, Debug)]
+///
+/// By default, the parameters are derived from the optimization goal of the
+/// constraint system with [`params::get_params`]. A fixed layout, e.g. one
+/// that matches an external verifier, can be used instead by allocating with
+/// [`EmulatedFpVar::new_variable_with_params`]. Variables with different
+/// layouts cannot be combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct NonNativeFieldConfig {
     /// The number of limbs (`BaseF` elements) used to represent a
     /// `TargetF` element. Highest limb first.
@@ -189,6 +235,25 @@ pub struct NonNativeFieldConfig {
     pub bits_per_limb: usize,
 }
 
+impl NonNativeFieldConfig {
+    /// Returns whether these parameters can be used to emulate `TargetF` over
+    /// `BaseF`.
+    ///
+    /// This requires that the limbs cover exactly the bits of `TargetF`,
+    /// with a nonempty top limb, and that the limbs are small enough to
+    /// leave room for the products and carries that arise when reducing.
+    pub fn is_valid_for<TargetF: ark_ff::PrimeField, BaseF: ark_ff::PrimeField>(&self) -> bool {
+        let target_bits = TargetF::MODULUS_BIT_SIZE as usize;
+        let base_bits = BaseF::MODULUS_BIT_SIZE as usize;
+        self.num_limbs > 0
+            && self.bits_per_limb > 0
+            && (self.num_limbs - 1) * self.bits_per_limb < target_bits
+            && target_bits <= self.num_limbs * self.bits_per_limb
+            && 2 * (self.bits_per_limb + 1) + 12 <= base_bits
+            && 2 * self.bits_per_limb + (ark_std::log2(self.num_limbs) as usize) < base_bits
+    }
+}
+
 mod allocated_field_var;
 pub use allocated_field_var::*;
 
@@ -200,3 +265,9 @@ pub use field_var::*;
 
 mod mul_result;
 pub use mul_result::*;
+
+mod public_input;
+pub use public_input::*;
+
+mod packed;
+pub use packed::*;
@@ -0,0 +1,244 @@
+use super::{
+    overhead, reduce::Reducer, AllocatedEmulatedFpVar, EmulatedFpVar, NonNativeFieldConfig,
+};
+use crate::{
+    alloc::AllocVar,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    range_check::RangeCheckGadget,
+    GR1CSVar,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::{
+    gr1cs::{ConstraintSystemRef, Result as R1CSResult, SynthesisError},
+    ns,
+};
+use ark_std::{marker::PhantomData, vec::Vec};
+use num_bigint::BigUint;
+
+/// The number of bits by which each slot of a [`PackedEmulatedFpVar`] exceeds
+/// `TargetF::MODULUS_BIT_SIZE`, which bounds how many additions the slots can
+/// absorb before they have to be reduced.
+pub const PACKED_SLOT_HEADROOM: usize = 8;
+
+/// Several emulated field elements in a single-limb layout, packed into one
+/// `BaseF` variable.
+///
+/// Element `i` occupies the bits `[i * slot_bits, (i + 1) * slot_bits)` of the
+/// variable, where `slot_bits` is `TargetF::MODULUS_BIT_SIZE +
+/// PACKED_SLOT_HEADROOM`. Adding two packed variables thus adds all of their
+/// elements at once. Other operations require [`Self::unpack`]ing them first.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct PackedEmulatedFpVar<TargetF: PrimeField, BaseF: PrimeField> {
+    /// The variable holding the slots
+    pub packed: FpVar<BaseF>,
+    /// The number of packed elements
+    pub num_slots: usize,
+    /// Every slot is smaller than `2^(TargetF::MODULUS_BIT_SIZE +
+    /// slot_overflow_bits)`
+    pub slot_overflow_bits: usize,
+    /// The limb layout of the packed elements
+    pub params: NonNativeFieldConfig,
+    #[doc(hidden)]
+    pub target_phantom: PhantomData<TargetF>,
+}
+
+impl<TargetF: PrimeField, BaseF: PrimeField> PackedEmulatedFpVar<TargetF, BaseF> {
+    /// The number of bits of each slot
+    pub fn slot_bits() -> usize {
+        TargetF::MODULUS_BIT_SIZE as usize + PACKED_SLOT_HEADROOM
+    }
+
+    /// The maximum number of elements that fit into one packed variable
+    pub fn capacity() -> usize {
+        (BaseF::MODULUS_BIT_SIZE as usize - 1) / Self::slot_bits()
+    }
+
+    /// Packs `elems` into one variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elems` is empty or has more than [`Self::capacity`]
+    /// elements, or if the elements are not all in the same single-limb
+    /// layout.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn pack(elems: &[EmulatedFpVar<TargetF, BaseF>]) -> R1CSResult<Self> {
+        assert!(
+            !elems.is_empty() && elems.len() <= Self::capacity(),
+            "cannot pack {} elements into one variable",
+            elems.len()
+        );
+
+        let cs = elems
+            .iter()
+            .fold(ConstraintSystemRef::None, |cs, elem| cs.or(elem.cs()));
+        let params = elems
+            .iter()
+            .find_map(EmulatedFpVar::params)
+            .unwrap_or_else(|| AllocatedEmulatedFpVar::<TargetF, BaseF>::default_params(&cs));
+        assert_eq!(
+            params.num_limbs, 1,
+            "only emulated field variables with a single limb can be packed"
+        );
+
+        let mut packed = FpVar::<BaseF>::zero();
+        let mut slot_overflow_bits = 0;
+        let mut shift = BaseF::one();
+        let slot_shift = BaseF::from(2u64).pow([Self::slot_bits() as u64]);
+        for elem in elems {
+            let limb = match elem {
+                EmulatedFpVar::Constant(c) => {
+                    let c_bigint: BigUint = (*c).into();
+                    FpVar::Constant(BaseF::from(c_bigint))
+                },
+                EmulatedFpVar::Var(v) => {
+                    assert_eq!(
+                        v.params, params,
+                        "cannot combine emulated field variables with different limb layouts"
+                    );
+                    let mut v = v.clone();
+                    let mut overflow_bits =
+                        overhead!(v.num_of_additions_over_normal_form + BaseF::one());
+                    if overflow_bits > PACKED_SLOT_HEADROOM {
+                        Reducer::reduce(&mut v)?;
+                        overflow_bits =
+                            overhead!(v.num_of_additions_over_normal_form + BaseF::one());
+                    }
+                    slot_overflow_bits = slot_overflow_bits.max(overflow_bits);
+                    v.limbs[0].clone()
+                },
+            };
+            packed += &(limb * shift);
+            shift *= slot_shift;
+        }
+
+        Ok(Self {
+            packed,
+            num_slots: elems.len(),
+            slot_overflow_bits,
+            params,
+            target_phantom: PhantomData,
+        })
+    }
+
+    /// Adds the elements of `other` to those of `self`, slot by slot.
+    ///
+    /// If the slots could overflow, the operand with the larger slots is
+    /// reduced first.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn add(&self, other: &Self) -> R1CSResult<Self> {
+        assert!(
+            self.num_slots == other.num_slots && self.params == other.params,
+            "cannot add packed variables with different layouts"
+        );
+
+        let mut this = self.clone();
+        let mut other = other.clone();
+        while this.slot_overflow_bits.max(other.slot_overflow_bits) + 1 > PACKED_SLOT_HEADROOM {
+            if this.slot_overflow_bits >= other.slot_overflow_bits {
+                this = this.reduce()?;
+            } else {
+                other = other.reduce()?;
+            }
+        }
+
+        Ok(Self {
+            packed: &this.packed + &other.packed,
+            num_slots: this.num_slots,
+            slot_overflow_bits: this.slot_overflow_bits.max(other.slot_overflow_bits) + 1,
+            params: this.params,
+            target_phantom: PhantomData,
+        })
+    }
+
+    /// Unpacks, reduces and repacks the elements, so that the slots have room
+    /// for further additions.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn reduce(&self) -> R1CSResult<Self> {
+        let mut elems = self.unpack()?;
+        for elem in elems.iter_mut() {
+            if let EmulatedFpVar::Var(v) = elem {
+                Reducer::reduce(v)?;
+            }
+        }
+        Self::pack(&elems)
+    }
+
+    /// Recovers the packed elements.
+    ///
+    /// This allocates every slot and checks that it fits into its bits; the
+    /// results are not reduced.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn unpack(&self) -> R1CSResult<Vec<EmulatedFpVar<TargetF, BaseF>>> {
+        if let FpVar::Constant(_) = self.packed {
+            return Ok(self
+                .slot_values()?
+                .into_iter()
+                .map(|slot| EmulatedFpVar::Constant(TargetF::from(slot)))
+                .collect());
+        }
+
+        let slot_bits = Self::slot_bits();
+        let value_bits = TargetF::MODULUS_BIT_SIZE as usize + self.slot_overflow_bits;
+        let slot_values = self.slot_values().ok();
+
+        let cs = self.packed.cs();
+        let mut slots = Vec::with_capacity(self.num_slots);
+        let mut sum = FpVar::<BaseF>::zero();
+        let mut shift = BaseF::one();
+        let slot_shift = BaseF::from(2u64).pow([slot_bits as u64]);
+        for i in 0..self.num_slots {
+            let slot = FpVar::new_witness(ns!(cs, "slot"), || {
+                slot_values
+                    .as_ref()
+                    .map(|values| BaseF::from(values[i].clone()))
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            slot.enforce_bit_length(value_bits)?;
+            sum += &(&slot * shift);
+            shift *= slot_shift;
+            slots.push(slot);
+        }
+        sum.enforce_equal(&self.packed)?;
+
+        let num_of_additions_over_normal_form =
+            BaseF::from(2u64).pow([self.slot_overflow_bits as u64]) - BaseF::one();
+        Ok(slots
+            .into_iter()
+            .map(|slot| {
+                EmulatedFpVar::Var(AllocatedEmulatedFpVar {
+                    cs: cs.clone(),
+                    limbs: ark_std::vec![slot],
+                    num_of_additions_over_normal_form,
+                    is_in_the_normal_form: false,
+                    params: self.params,
+                    target_phantom: PhantomData,
+                })
+            })
+            .collect())
+    }
+
+    /// The integer values of the slots
+    fn slot_values(&self) -> R1CSResult<Vec<BigUint>> {
+        let slot_bits = Self::slot_bits();
+        let bits = self.packed.value()?.into_bigint().to_bits_le();
+        Ok((0..self.num_slots)
+            .map(|i| BaseF::BigInt::from_bits_le(&bits[i * slot_bits..(i + 1) * slot_bits]).into())
+            .collect())
+    }
+}
+
+impl<TargetF: PrimeField, BaseF: PrimeField> GR1CSVar<BaseF>
+    for PackedEmulatedFpVar<TargetF, BaseF>
+{
+    type Value = Vec<TargetF>;
+
+    fn cs(&self) -> ConstraintSystemRef<BaseF> {
+        self.packed.cs()
+    }
+
+    fn value(&self) -> R1CSResult<Self::Value> {
+        Ok(self.slot_values()?.into_iter().map(TargetF::from).collect())
+    }
+}
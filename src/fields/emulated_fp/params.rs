@@ -1,9 +1,15 @@
 use super::NonNativeFieldConfig;
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::OptimizationGoal;
+use ark_std::{
+    cmp::min,
+    ops::{Add, AddAssign},
+};
 
 /// Obtain the parameters from a `ConstraintSystem`'s cache or generate a new
 /// one
 #[must_use]
-pub const fn get_params(
+pub fn get_params(
     target_field_size: usize,
     base_field_size: usize,
     optimization_type: OptimizationType,
@@ -25,8 +31,64 @@ pub enum OptimizationType {
     Weight,
 }
 
+impl From<OptimizationGoal> for OptimizationType {
+    /// The optimization type that matches the optimization goal of a
+    /// constraint system
+    fn from(goal: OptimizationGoal) -> Self {
+        match goal {
+            OptimizationGoal::None => OptimizationType::Constraints,
+            OptimizationGoal::Constraints => OptimizationType::Constraints,
+            OptimizationGoal::Weight => OptimizationType::Weight,
+        }
+    }
+}
+
+/// The number of bits of `x`, plus one if `x` is not a power of two, i.e.
+/// the `overhead!` of `x` in the gadgets
+const fn overhead(x: usize) -> usize {
+    if x == 0 {
+        return 0;
+    }
+    let num_bits = (usize::BITS - x.leading_zeros()) as usize;
+    if x.is_power_of_two() {
+        num_bits
+    } else {
+        num_bits + 1
+    }
+}
+
+/// Whether the product of two freshly allocated elements with `num_of_limbs`
+/// limbs of `limb_size` bits fits into the base field and can be reduced,
+/// mirroring the checks in `Reducer::pre_mul_reduce` and
+/// `AllocatedMulResultVar::reduce`
+const fn supports_mul(
+    base_field_prime_length: usize,
+    num_of_limbs: usize,
+    limb_size: usize,
+) -> bool {
+    2 * (limb_size + 1) + overhead(num_of_limbs) < base_field_prime_length
+        && 2 * limb_size + overhead(2) + 6 <= base_field_prime_length
+}
+
+/// The size of the largest base fields for which [`find_parameters`] ranks
+/// limb layouts with the cost model of [`estimate_costs_with_params`]
+///
+/// Limbs in such fields (e.g. Goldilocks, BabyBear or Mersenne31) are only a
+/// few bits wide, and the cost formulas for large base fields do not account
+/// for the many carries between them.
+const SMALL_BASE_FIELD_BITS: usize = 64;
+
 /// A function to search for parameters for emulated field gadgets
-pub const fn find_parameters(
+///
+/// For small base fields (e.g. 31- or 64-bit fields) the search only
+/// considers limb sizes for which multiplication remains possible, which
+/// typically results in many small limbs, and ranks them by the estimated
+/// cost of a multiplication; see [`estimate_costs_with_params`].
+///
+/// # Panics
+///
+/// Panics if the base field is too small for any limb layout.
+pub fn find_parameters(
     base_field_prime_length: usize,
     target_field_prime_bit_length: usize,
     optimization_type: OptimizationType,
@@ -37,7 +99,8 @@ pub const fn find_parameters(
     let mut min_cost_num_of_limbs = 0usize;
 
     let surfeit = 10;
-    let mut max_limb_size = (base_field_prime_length - 1 - surfeit - 1) / 2 - 1;
+    let mut max_limb_size =
+        ((base_field_prime_length.saturating_sub(surfeit + 2)) / 2).saturating_sub(1);
     if max_limb_size > target_field_prime_bit_length {
         max_limb_size = target_field_prime_bit_length;
     }
@@ -45,42 +108,27 @@ pub const fn find_parameters(
 
     while limb_size <= max_limb_size {
         let num_of_limbs = (target_field_prime_bit_length + limb_size - 1) / limb_size;
+        if !supports_mul(base_field_prime_length, num_of_limbs, limb_size) {
+            limb_size += 1;
+            continue;
+        }
 
-        let group_size =
-            (base_field_prime_length - 1 - surfeit - 1 - 1 - limb_size + limb_size - 1) / limb_size;
-        let num_of_groups = (2 * num_of_limbs - 1 + group_size - 1) / group_size;
-
-        let mut this_cost = 0;
-
-        match optimization_type {
-            OptimizationType::Constraints => {
-                this_cost += 2 * num_of_limbs - 1;
-            },
-            OptimizationType::Weight => {
-                this_cost += 6 * num_of_limbs * num_of_limbs;
-            },
-        };
-
-        match optimization_type {
-            OptimizationType::Constraints => {
-                this_cost += target_field_prime_bit_length; // allocation of k
-                this_cost += target_field_prime_bit_length + num_of_limbs; // allocation of r
-                                                                           // this_cost += 2 * num_of_limbs - 1; // compute kp
-                this_cost += num_of_groups + (num_of_groups - 1) * (limb_size * 2 + surfeit) + 1;
-                // equality check
-            },
-            OptimizationType::Weight => {
-                this_cost += target_field_prime_bit_length * 3 + target_field_prime_bit_length; // allocation of k
-                this_cost += target_field_prime_bit_length * 3
-                    + target_field_prime_bit_length
-                    + num_of_limbs; // allocation of r
-                this_cost += num_of_limbs * num_of_limbs + 2 * (2 * num_of_limbs - 1); // compute kp
-                this_cost += num_of_limbs
-                    + num_of_groups
-                    + 6 * num_of_groups
-                    + (num_of_groups - 1) * (2 * limb_size + surfeit) * 4
-                    + 2; // equality check
-            },
+        let this_cost = if base_field_prime_length <= SMALL_BASE_FIELD_BITS {
+            small_base_mul_cost(
+                base_field_prime_length,
+                target_field_prime_bit_length,
+                num_of_limbs,
+                limb_size,
+                optimization_type,
+            )
+        } else {
+            large_base_mul_cost(
+                base_field_prime_length,
+                target_field_prime_bit_length,
+                num_of_limbs,
+                limb_size,
+                optimization_type,
+            )
         };
 
         if !found || this_cost < min_cost {
@@ -93,5 +141,444 @@ pub const fn find_parameters(
         limb_size += 1;
     }
 
+    assert!(
+        found,
+        "the base field is too small to emulate the target field"
+    );
+
     (min_cost_num_of_limbs, min_cost_limb_size)
 }
+
+/// The cost of a multiplication in the given limb layout, with the formulas
+/// for large base fields
+const fn large_base_mul_cost(
+    base_field_prime_length: usize,
+    target_field_prime_bit_length: usize,
+    num_of_limbs: usize,
+    limb_size: usize,
+    optimization_type: OptimizationType,
+) -> usize {
+    let surfeit = 10;
+    let group_size =
+        (base_field_prime_length - 1 - surfeit - 1 - 1 - limb_size + limb_size - 1) / limb_size;
+    let num_of_groups = (2 * num_of_limbs - 1 + group_size - 1) / group_size;
+
+    let mut this_cost = 0;
+
+    match optimization_type {
+        OptimizationType::Constraints => {
+            this_cost += 2 * num_of_limbs - 1;
+        },
+        OptimizationType::Weight => {
+            this_cost += 6 * num_of_limbs * num_of_limbs;
+        },
+    };
+
+    match optimization_type {
+        OptimizationType::Constraints => {
+            this_cost += target_field_prime_bit_length; // allocation of k
+            this_cost += target_field_prime_bit_length + num_of_limbs; // allocation of r
+                                                                       // this_cost += 2 * num_of_limbs - 1; // compute kp
+            this_cost += num_of_groups + (num_of_groups - 1) * (limb_size * 2 + surfeit) + 1;
+            // equality check
+        },
+        OptimizationType::Weight => {
+            this_cost += target_field_prime_bit_length * 3 + target_field_prime_bit_length; // allocation of k
+            this_cost +=
+                target_field_prime_bit_length * 3 + target_field_prime_bit_length + num_of_limbs; // allocation of r
+            this_cost += num_of_limbs * num_of_limbs + 2 * (2 * num_of_limbs - 1); // compute kp
+            this_cost += num_of_limbs
+                + num_of_groups
+                + 6 * num_of_groups
+                + (num_of_groups - 1) * (2 * limb_size + surfeit) * 4
+                + 2; // equality check
+        },
+    };
+
+    this_cost
+}
+
+/// The cost of a multiplication in the given limb layout according to
+/// [`CostModel`]: its constraints if optimizing for constraints, and its
+/// nonzero entries with all linear combinations inlined, which bound the
+/// weight from above, if optimizing for weight
+fn small_base_mul_cost(
+    base_field_prime_length: usize,
+    target_field_prime_bit_length: usize,
+    num_of_limbs: usize,
+    limb_size: usize,
+    optimization_type: OptimizationType,
+) -> usize {
+    let model = CostModel {
+        target_bits: target_field_prime_bit_length,
+        base_bits: base_field_prime_length,
+        num_limbs: num_of_limbs,
+        bits_per_limb: limb_size,
+        optimization_type,
+    };
+    let mul = model.mul();
+    match optimization_type {
+        OptimizationType::Constraints => mul.constraints,
+        OptimizationType::Weight => mul.nonzeros.expect("the cost model counts nonzeros"),
+    }
+}
+
+/// The cost of an operation in the constraint system
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OperationCost {
+    /// The number of constraints
+    pub constraints: usize,
+    /// The number of witness variables
+    pub witness_variables: usize,
+    /// The number of nonzero entries in the R1CS matrices, once all linear
+    /// combinations are inlined
+    ///
+    /// This is `None` for [`OptimizationType::Weight`]: with
+    /// `OptimizationGoal::Weight`, the constraint system outlines linear
+    /// combinations when it is finalized, which the estimate does not model.
+    pub nonzeros: Option<usize>,
+}
+
+impl OperationCost {
+    /// The cost of an operation that adds nothing to the constraint system
+    pub const ZERO: Self = Self {
+        constraints: 0,
+        witness_variables: 0,
+        nonzeros: Some(0),
+    };
+}
+
+impl Add for OperationCost {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            constraints: self.constraints + other.constraints,
+            witness_variables: self.witness_variables + other.witness_variables,
+            nonzeros: self.nonzeros.zip(other.nonzeros).map(|(a, b)| a + b),
+        }
+    }
+}
+
+impl AddAssign for OperationCost {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// The estimated costs of the operations of [`EmulatedFpVar`] on freshly
+/// allocated witnesses
+///
+/// [`EmulatedFpVar`]: super::EmulatedFpVar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmulatedCosts {
+    /// The limb layout the costs are estimated for
+    pub params: NonNativeFieldConfig,
+    /// Allocating a witness
+    pub alloc: OperationCost,
+    /// Adding two elements
+    pub add: OperationCost,
+    /// Multiplying two elements, i.e. `mul_without_reduce` followed by
+    /// `reduce`
+    pub mul: OperationCost,
+    /// Multiplying two elements without reducing the product
+    pub mul_without_reduce: OperationCost,
+    /// Reducing the result of `mul_without_reduce`
+    pub reduce: OperationCost,
+    /// Inverting an element
+    pub inverse: OperationCost,
+    /// Enforcing that two elements are equal
+    pub enforce_equal: OperationCost,
+}
+
+/// Estimates the costs of operations on `EmulatedFpVar<TargetF, BaseF>` in a
+/// constraint system optimized for `optimization_type`, without
+/// synthesizing any circuit.
+#[must_use]
+pub fn estimate_costs<TargetF: PrimeField, BaseF: PrimeField>(
+    optimization_type: OptimizationType,
+) -> EmulatedCosts {
+    let params = get_params(
+        TargetF::MODULUS_BIT_SIZE as usize,
+        BaseF::MODULUS_BIT_SIZE as usize,
+        optimization_type,
+    );
+    estimate_costs_with_params::<TargetF, BaseF>(params, optimization_type)
+}
+
+/// Estimates the costs of operations on `EmulatedFpVar<TargetF, BaseF>` in
+/// the limb layout `params`; see [`estimate_costs`].
+#[must_use]
+pub fn estimate_costs_with_params<TargetF: PrimeField, BaseF: PrimeField>(
+    params: NonNativeFieldConfig,
+    optimization_type: OptimizationType,
+) -> EmulatedCosts {
+    let model = CostModel {
+        target_bits: TargetF::MODULUS_BIT_SIZE as usize,
+        base_bits: BaseF::MODULUS_BIT_SIZE as usize,
+        num_limbs: params.num_limbs,
+        bits_per_limb: params.bits_per_limb,
+        optimization_type,
+    };
+
+    let mul_without_reduce = model.mul_without_reduce();
+    let reduce = model.reduce_mul_result(4);
+    let mul = model.mul();
+
+    let supported = |cost: OperationCost| match optimization_type {
+        OptimizationType::Constraints => cost,
+        OptimizationType::Weight => OperationCost {
+            nonzeros: None,
+            ..cost
+        },
+    };
+    EmulatedCosts {
+        params,
+        alloc: supported(model.alloc()),
+        add: supported(model.add(1, 1)),
+        mul: supported(mul),
+        mul_without_reduce: supported(mul_without_reduce),
+        reduce: supported(reduce),
+        inverse: supported(model.alloc() + mul + model.enforce_equal(1, None)),
+        enforce_equal: supported(model.enforce_equal(1, Some(1))),
+    }
+}
+
+/// Mirrors the constraints that the emulated field gadgets generate
+struct CostModel {
+    target_bits: usize,
+    base_bits: usize,
+    num_limbs: usize,
+    bits_per_limb: usize,
+    optimization_type: OptimizationType,
+}
+
+impl CostModel {
+    /// The number of bits of the most significant limb in the normal form
+    fn top_limb_bits(&self) -> usize {
+        self.target_bits - (self.num_limbs - 1) * self.bits_per_limb
+    }
+
+    /// `FpVar::enforce_bit_length`: one Boolean per bit, and the recomposition
+    fn range_check(&self, num_bits: usize) -> OperationCost {
+        if num_bits >= self.base_bits {
+            return OperationCost::ZERO;
+        }
+        OperationCost {
+            constraints: num_bits + 1,
+            witness_variables: num_bits,
+            nonzeros: Some(3 * num_bits + num_bits + 2),
+        }
+    }
+
+    /// `AllocatedEmulatedFpVar::new_witness`: the limbs and their range checks
+    fn alloc(&self) -> OperationCost {
+        let mut cost = OperationCost {
+            witness_variables: self.num_limbs,
+            ..OperationCost::ZERO
+        };
+        for _ in 0..self.num_limbs - 1 {
+            cost += self.range_check(min(self.bits_per_limb, self.base_bits - 1));
+        }
+        cost += self.range_check(min(self.top_limb_bits(), self.base_bits - 1));
+        cost
+    }
+
+    /// `AllocatedEmulatedFpVar::add` of elements with the given num of
+    /// additions over the normal form
+    fn add(&self, num_of_additions: usize, other_num_of_additions: usize) -> OperationCost {
+        let num_of_additions = num_of_additions + other_num_of_additions + 1;
+        let surfeit = overhead(num_of_additions + 1) + 1;
+        if self.base_bits > 2 * self.bits_per_limb + surfeit + 1 {
+            OperationCost::ZERO
+        } else {
+            self.reduce(num_of_additions, 2)
+        }
+    }
+
+    /// `AllocatedEmulatedFpVar::mul` of two fresh witnesses
+    fn mul(&self) -> OperationCost {
+        // Fresh witnesses carry one addition over the normal form, so their
+        // products carry `(1 + 1) * (1 + 1)`.
+        self.mul_without_reduce() + self.reduce_mul_result(4)
+    }
+
+    /// `AllocatedEmulatedFpVar::mul_without_reduce`
+    fn mul_without_reduce(&self) -> OperationCost {
+        let n = self.num_limbs;
+        match self.optimization_type {
+            // One product per pair of limbs
+            OptimizationType::Weight => OperationCost {
+                constraints: n * n,
+                witness_variables: n * n,
+                nonzeros: Some(3 * n * n),
+            },
+            // The product limbs, and two constraints per evaluation point
+            OptimizationType::Constraints => OperationCost {
+                constraints: 2 * (2 * n - 1),
+                witness_variables: 2 * (2 * n - 1),
+                nonzeros: Some((2 * n - 1) * (4 * n + 2)),
+            },
+        }
+    }
+
+    /// `AllocatedMulResultVar::reduce` of a product whose factors carry
+    /// `prod_of_num_of_additions` in total
+    fn reduce_mul_result(&self, prod_of_num_of_additions: usize) -> OperationCost {
+        let n = self.num_limbs;
+        let b = self.bits_per_limb;
+        let surfeit = overhead(prod_of_num_of_additions + 1) + 1 + 1;
+        let k_bits = self.target_bits + surfeit;
+
+        if let Some(cost) = self.reduce_single_limb(k_bits, 1) {
+            return cost;
+        }
+
+        let mut cost = OperationCost {
+            constraints: k_bits,
+            witness_variables: k_bits,
+            nonzeros: Some(3 * k_bits),
+        };
+        cost += self.alloc();
+
+        let weight = self.optimization_type == OptimizationType::Weight;
+        // Positions are counted from the least significant limb
+        let left_terms = |lo: usize, hi: usize| {
+            if weight {
+                (lo..hi).map(|i| min(i, 2 * n - 2 - i) + 1).sum::<usize>()
+            } else {
+                hi - lo
+            }
+        };
+        // The bits of the limbs of `k` that contribute to the positions, and
+        // the limbs of `r`
+        let right_terms = |lo: usize, hi: usize| {
+            let k_limb_bits = |j: usize| {
+                if j == n - 1 {
+                    k_bits - (n - 1) * b
+                } else {
+                    b
+                }
+            };
+            let k_terms: usize = (lo.saturating_sub(n - 1)..min(hi, n))
+                .map(k_limb_bits)
+                .sum();
+            k_terms + min(hi, n).saturating_sub(lo)
+        };
+        cost += self.group_and_check_equality(surfeit, 2 * b, 2 * n - 1, left_terms, right_terms);
+        cost
+    }
+
+    /// `Reducer::reduce` of an element with `num_of_additions` over the normal
+    /// form, whose limbs are linear combinations of `limb_terms` variables
+    fn reduce(&self, num_of_additions: usize, limb_terms: usize) -> OperationCost {
+        let quotient_bits = overhead(num_of_additions + 1) + 1;
+        if let Some(cost) = self.reduce_single_limb(quotient_bits, limb_terms) {
+            return cost;
+        }
+        self.alloc() + self.conditional_enforce_equal(num_of_additions, Some(1), limb_terms)
+    }
+
+    /// `Reducer::reduce_single_limb`, if it applies
+    fn reduce_single_limb(&self, quotient_bits: usize, limb_terms: usize) -> Option<OperationCost> {
+        if self.num_limbs != 1 || quotient_bits + self.target_bits + 1 >= self.base_bits {
+            return None;
+        }
+        let mut cost = self.alloc();
+        cost.witness_variables += 1;
+        cost += self.range_check(quotient_bits);
+        cost += OperationCost {
+            constraints: 1,
+            witness_variables: 0,
+            nonzeros: Some(limb_terms + 3),
+        };
+        Some(cost)
+    }
+
+    /// `EmulatedFpVar::enforce_equal` of an element with `num_of_additions`
+    /// over the normal form and a variable (or a constant, if `None` is passed
+    /// as its num of additions), both with limbs of a single variable
+    fn enforce_equal(
+        &self,
+        num_of_additions: usize,
+        other_num_of_additions: Option<usize>,
+    ) -> OperationCost {
+        self.conditional_enforce_equal(num_of_additions, other_num_of_additions, 1)
+    }
+
+    /// `AllocatedEmulatedFpVar::conditional_enforce_equal`, where the limbs of
+    /// `self` are linear combinations of `limb_terms` variables
+    fn conditional_enforce_equal(
+        &self,
+        num_of_additions: usize,
+        other_num_of_additions: Option<usize>,
+        limb_terms: usize,
+    ) -> OperationCost {
+        let (other_additions, other_terms) = match other_num_of_additions {
+            Some(additions) => (additions, 1),
+            None => (0, 0),
+        };
+        let delta_additions = num_of_additions + 2 * (other_additions + 1);
+        let surfeit = overhead(delta_additions + 1) + 1;
+
+        // `k`
+        let mut cost = OperationCost {
+            witness_variables: 1,
+            ..OperationCost::ZERO
+        };
+        cost += self.range_check(surfeit);
+
+        let left_terms = |lo: usize, hi: usize| (hi - lo) * (limb_terms + other_terms);
+        let right_terms = |_: usize, _: usize| 1;
+        cost += self.group_and_check_equality(
+            surfeit,
+            self.bits_per_limb,
+            self.num_limbs,
+            left_terms,
+            right_terms,
+        );
+        cost
+    }
+
+    /// `Reducer::group_and_check_equality` of `num_positions` limbs of
+    /// `bits_per_limb` bits, shifted by the limb size of the layout
+    fn group_and_check_equality(
+        &self,
+        surfeit: usize,
+        bits_per_limb: usize,
+        num_positions: usize,
+        left_terms: impl Fn(usize, usize) -> usize,
+        right_terms: impl Fn(usize, usize) -> usize,
+    ) -> OperationCost {
+        let shift_per_limb = self.bits_per_limb;
+        let num_limb_in_a_group =
+            (self.base_bits - 1 - surfeit - 1 - 1 - 1 - (bits_per_limb - shift_per_limb))
+                / shift_per_limb;
+        let num_groups = (num_positions + num_limb_in_a_group - 1) / num_limb_in_a_group;
+
+        let mut cost = OperationCost::ZERO;
+        for group_id in 0..num_groups {
+            let lo = group_id * num_limb_in_a_group;
+            let hi = min(lo + num_limb_in_a_group, num_positions);
+
+            // The carry, and the equation of the group
+            cost += OperationCost {
+                constraints: 1,
+                witness_variables: 1,
+                nonzeros: Some(
+                    left_terms(lo, hi) + right_terms(lo, hi) + 3 + usize::from(group_id > 0),
+                ),
+            };
+            if group_id == num_groups - 1 {
+                cost += OperationCost {
+                    constraints: 1,
+                    witness_variables: 0,
+                    nonzeros: Some(3),
+                };
+            } else {
+                cost += self.range_check(surfeit + bits_per_limb);
+            }
+        }
+        cost
+    }
+}
@@ -0,0 +1,93 @@
+use super::{params::get_params, reduce::bigint_to_limbs, NonNativeFieldConfig};
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::gr1cs::OptimizationGoal;
+use ark_std::vec::Vec;
+
+/// Returns the limb layout that [`EmulatedFpVar`] uses for new variables in a
+/// constraint system with the optimization goal `goal`.
+///
+/// A verifier that does not have access to the constraint system can use
+/// this to obtain the layout for [`to_public_input`].
+///
+/// [`EmulatedFpVar`]: super::EmulatedFpVar
+pub fn params_for_goal<TargetF: PrimeField, BaseF: PrimeField>(
+    goal: OptimizationGoal,
+) -> NonNativeFieldConfig {
+    get_params(
+        TargetF::MODULUS_BIT_SIZE as usize,
+        BaseF::MODULUS_BIT_SIZE as usize,
+        goal.into(),
+    )
+}
+
+/// Computes the public inputs that allocating `value` with
+/// [`AllocationMode::Input`] in the layout `params` produces.
+///
+/// These are the `params.num_limbs` limbs of `value`, highest limb first.
+/// Use [`params_for_goal`] to obtain the layout that [`EmulatedFpVar::new_input`]
+/// picks, or the layout passed to [`EmulatedFpVar::new_variable_with_params`].
+///
+/// [`AllocationMode::Input`]: crate::alloc::AllocationMode::Input
+/// [`EmulatedFpVar::new_input`]: crate::alloc::AllocVar::new_input
+/// [`EmulatedFpVar::new_variable_with_params`]: super::EmulatedFpVar::new_variable_with_params
+pub fn to_public_input<TargetF: PrimeField, BaseF: PrimeField>(
+    value: &TargetF,
+    params: &NonNativeFieldConfig,
+) -> Vec<BaseF> {
+    bigint_to_limbs::<TargetF, BaseF>(&value.into_bigint(), params)
+}
+
+/// Computes the public inputs that allocating each of `values` in turn with
+/// [`AllocationMode::Input`] in the layout `params` produces.
+///
+/// This is the concatenation of [`to_public_input`] of each value, e.g. the
+/// inputs of a `Vec<EmulatedFpVar<TargetF, BaseF>>` allocated with `new_input`.
+///
+/// [`AllocationMode::Input`]: crate::alloc::AllocationMode::Input
+pub fn to_public_inputs<TargetF: PrimeField, BaseF: PrimeField>(
+    values: &[TargetF],
+    params: &NonNativeFieldConfig,
+) -> Vec<BaseF> {
+    values
+        .iter()
+        .flat_map(|value| to_public_input::<TargetF, BaseF>(value, params))
+        .collect()
+}
+
+/// The number of bits of a `TargetF` element that each packed public input
+/// carries, i.e. the capacity of `BaseF`.
+pub(crate) fn packed_chunk_size<BaseF: PrimeField>() -> usize {
+    BaseF::MODULUS_BIT_SIZE as usize - 1
+}
+
+/// Computes the public inputs that allocating `value` with
+/// [`EmulatedFpVar::new_packed_input`] produces.
+///
+/// The little-endian bits of `value` are split into chunks of
+/// `BaseF::MODULUS_BIT_SIZE - 1` bits, lowest chunk first, so that a
+/// `TargetF` element takes `ceil(TargetF::MODULUS_BIT_SIZE / (BaseF::MODULUS_BIT_SIZE - 1))`
+/// public inputs regardless of the limb layout.
+///
+/// [`EmulatedFpVar::new_packed_input`]: super::EmulatedFpVar::new_packed_input
+pub fn to_packed_public_input<TargetF: PrimeField, BaseF: PrimeField>(
+    value: &TargetF,
+) -> Vec<BaseF> {
+    let mut bits = value.into_bigint().to_bits_le();
+    bits.truncate(TargetF::MODULUS_BIT_SIZE as usize);
+    bits.chunks(packed_chunk_size::<BaseF>())
+        .map(|chunk| BaseF::from_bigint(BaseF::BigInt::from_bits_le(chunk)).unwrap())
+        .collect()
+}
+
+/// Computes the public inputs that allocating each of `values` in turn with
+/// [`EmulatedFpVar::new_packed_input`] produces.
+///
+/// [`EmulatedFpVar::new_packed_input`]: super::EmulatedFpVar::new_packed_input
+pub fn to_packed_public_inputs<TargetF: PrimeField, BaseF: PrimeField>(
+    values: &[TargetF],
+) -> Vec<BaseF> {
+    values
+        .iter()
+        .flat_map(to_packed_public_input::<TargetF, BaseF>)
+        .collect()
+}
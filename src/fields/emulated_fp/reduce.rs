@@ -1,16 +1,14 @@
-use super::{overhead, params::get_params, AllocatedEmulatedFpVar};
+use super::{overhead, AllocatedEmulatedFpVar, NonNativeFieldConfig};
 use crate::{
-    alloc::AllocVar,
+    alloc::{AllocVar, AllocationMode},
     boolean::Boolean,
     eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
+    range_check::RangeCheckGadget,
     GR1CSVar,
 };
-use ark_ff::{biginteger::BigInteger, BitIteratorBE, One, PrimeField, Zero};
-use ark_relations::{
-    gr1cs::{ConstraintSystemRef, Result as R1CSResult},
-    ns,
-};
+use ark_ff::{biginteger::BigInteger, One, PrimeField, Zero};
+use ark_relations::{gr1cs::Result as R1CSResult, ns};
 use ark_std::{cmp::min, marker::PhantomData, vec, vec::Vec};
 use num_bigint::BigUint;
 use num_integer::Integer;
@@ -34,6 +32,30 @@ pub fn limbs_to_bigint<BaseF: PrimeField>(bits_per_limb: usize, limbs: &[BaseF])
     val
 }
 
+/// Splits `elem` into `params.num_limbs` limbs of `params.bits_per_limb`
+/// bits, highest limb first.
+pub fn bigint_to_limbs<TargetF: PrimeField, BaseF: PrimeField>(
+    elem: &<TargetF as PrimeField>::BigInt,
+    params: &NonNativeFieldConfig,
+) -> Vec<BaseF> {
+    // push the lower limbs first
+    let mut limbs: Vec<BaseF> = Vec::new();
+    let mut cur = *elem;
+    for _ in 0..params.num_limbs {
+        let cur_bits = cur.to_bits_be(); // `to_bits` is big endian
+        let cur_mod_r = <BaseF as PrimeField>::BigInt::from_bits_be(
+            &cur_bits[cur_bits.len() - params.bits_per_limb..],
+        ); // therefore, the lowest `bits_per_non_top_limb` bits is what we want.
+        limbs.push(BaseF::from_bigint(cur_mod_r).unwrap());
+        cur >>= params.bits_per_limb as u32;
+    }
+
+    // then we reserve, so that the limbs are ``big limb first''
+    limbs.reverse();
+
+    limbs
+}
+
 pub fn bigint_to_basefield<BaseF: PrimeField>(bigint: &BigUint) -> BaseF {
     let mut val = BaseF::zero();
     let mut cur = BaseF::one();
@@ -51,6 +73,25 @@ pub fn bigint_to_basefield<BaseF: PrimeField>(bigint: &BigUint) -> BaseF {
     val
 }
 
+/// Computes `overhead!(x * factor)` over the integers.
+///
+/// For small base fields the product can exceed the modulus, in which case
+/// computing it in `BaseF` would wrap around and underestimate the overhead.
+pub(crate) fn overhead_of_product<BaseF: PrimeField>(x: BaseF, factor: usize) -> usize {
+    let num: BigUint = x.into();
+    let num = num * BigUint::from(factor);
+    if num.is_zero() {
+        return 0;
+    }
+
+    let num_bits = num.bits() as usize;
+    if num == BigUint::one() << (num_bits - 1) {
+        num_bits
+    } else {
+        num_bits + 1
+    }
+}
+
 /// the collections of methods for reducing the presentations
 pub struct Reducer<TargetF: PrimeField, BaseF: PrimeField> {
     pub target_phantom: PhantomData<TargetF>,
@@ -64,70 +105,83 @@ impl<TargetF: PrimeField, BaseF: PrimeField> Reducer<TargetF, BaseF> {
     /// some bits are always zero.
     #[tracing::instrument(target = "gr1cs")]
     pub fn limb_to_bits(limb: &FpVar<BaseF>, num_bits: usize) -> R1CSResult<Vec<Boolean<BaseF>>> {
-        let cs = limb.cs();
-
         let num_bits = min(BaseF::MODULUS_BIT_SIZE as usize - 1, num_bits);
-        let mut bits_considered = Vec::with_capacity(num_bits);
-        let limb_value = limb.value().unwrap_or_default();
-
-        let num_bits_to_shave = BaseF::BigInt::NUM_LIMBS * 64 - (BaseF::MODULUS_BIT_SIZE as usize);
+        let mut bits = limb.to_bits_le_with_bit_length(num_bits)?;
+        bits.reverse();
+        Ok(bits)
+    }
 
-        for b in BitIteratorBE::new(limb_value.into_bigint())
-            .skip(num_bits_to_shave + (BaseF::MODULUS_BIT_SIZE as usize - num_bits))
+    /// Reduction to the normal form
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn reduce(elem: &mut AllocatedEmulatedFpVar<TargetF, BaseF>) -> R1CSResult<()> {
+        let quotient_bits = overhead!(elem.num_of_additions_over_normal_form + BaseF::one()) + 1;
+        if let Some(new_elem) =
+            Self::reduce_single_limb(&elem.limbs[0], quotient_bits, elem.params)?
         {
-            bits_considered.push(b);
+            *elem = new_elem;
+            return Ok(());
         }
 
-        if cs == ConstraintSystemRef::None {
-            let mut bits = vec![];
-            for b in bits_considered {
-                bits.push(Boolean::<BaseF>::Constant(b));
-            }
-
-            Ok(bits)
-        } else {
-            let mut bits = vec![];
-            for b in bits_considered {
-                bits.push(Boolean::<BaseF>::new_witness(
-                    ark_relations::ns!(cs, "bit"),
-                    || Ok(b),
-                )?);
-            }
-
-            let mut bit_sum = FpVar::<BaseF>::zero();
-            let mut coeff = BaseF::one();
-
-            for bit in bits.iter().rev() {
-                bit_sum += <FpVar<BaseF> as From<Boolean<BaseF>>>::from((*bit).clone()) * coeff;
-                coeff.double_in_place();
-            }
-
-            bit_sum.enforce_equal(limb)?;
+        let new_elem = AllocatedEmulatedFpVar::new_variable_with_params(
+            ns!(elem.cs(), "normal_form"),
+            || Ok(elem.value().unwrap_or_default()),
+            AllocationMode::Witness,
+            elem.params,
+        )?;
+        elem.conditional_enforce_equal(&new_elem, &Boolean::TRUE)?;
+        *elem = new_elem;
 
-            Ok(bits)
-        }
+        Ok(())
     }
 
-    /// Reduction to the normal form
+    /// Reduction of an element whose layout has a single limb, i.e. of a
+    /// `TargetF` that is small compared to `BaseF`.
+    ///
+    /// Allocates `r` and a quotient `q` of `quotient_bits` bits, and enforces
+    /// `limb = q * p + r` with a single constraint instead of grouping limbs
+    /// and propagating carries. Returns `None` if the layout has more than one
+    /// limb, or if `q * p + r` could wrap around `BaseF`.
     #[tracing::instrument(target = "gr1cs")]
-    pub fn reduce(elem: &mut AllocatedEmulatedFpVar<TargetF, BaseF>) -> R1CSResult<()> {
-        let new_elem = AllocatedEmulatedFpVar::new_witness(ns!(elem.cs(), "normal_form"), || {
-            Ok(elem.value().unwrap_or_default())
+    pub fn reduce_single_limb(
+        limb: &FpVar<BaseF>,
+        quotient_bits: usize,
+        params: NonNativeFieldConfig,
+    ) -> R1CSResult<Option<AllocatedEmulatedFpVar<TargetF, BaseF>>> {
+        if params.num_limbs != 1
+            || quotient_bits + TargetF::MODULUS_BIT_SIZE as usize + 1
+                >= BaseF::MODULUS_BIT_SIZE as usize
+        {
+            return Ok(None);
+        }
+
+        let cs = limb.cs();
+        let p_bigint: BigUint = TargetF::MODULUS.into();
+
+        let r = AllocatedEmulatedFpVar::new_variable_with_params(
+            ns!(cs, "r"),
+            || {
+                let limb_bigint: BigUint = limb.value()?.into();
+                Ok(TargetF::from(limb_bigint))
+            },
+            AllocationMode::Witness,
+            params,
+        )?;
+        let q = FpVar::new_witness(ns!(cs, "q"), || {
+            let limb_bigint: BigUint = limb.value()?.into();
+            Ok(bigint_to_basefield::<BaseF>(&(limb_bigint / &p_bigint)))
         })?;
-        elem.conditional_enforce_equal(&new_elem, &Boolean::TRUE)?;
-        *elem = new_elem;
+        q.enforce_bit_length(quotient_bits)?;
 
-        Ok(())
+        let p = bigint_to_basefield::<BaseF>(&p_bigint);
+        limb.enforce_equal(&(q * p + &r.limbs[0]))?;
+
+        Ok(Some(r))
     }
 
     /// Reduction to be enforced after additions
     #[tracing::instrument(target = "gr1cs")]
     pub fn post_add_reduce(elem: &mut AllocatedEmulatedFpVar<TargetF, BaseF>) -> R1CSResult<()> {
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            elem.get_optimization_type(),
-        );
+        let params = elem.params;
         let surfeit = overhead!(elem.num_of_additions_over_normal_form + BaseF::one()) + 1;
 
         if BaseF::MODULUS_BIT_SIZE as usize > 2 * params.bits_per_limb + surfeit + 1 {
@@ -144,16 +198,9 @@ impl<TargetF: PrimeField, BaseF: PrimeField> Reducer<TargetF, BaseF> {
         elem: &mut AllocatedEmulatedFpVar<TargetF, BaseF>,
         elem_other: &mut AllocatedEmulatedFpVar<TargetF, BaseF>,
     ) -> R1CSResult<()> {
-        assert_eq!(
-            elem.get_optimization_type(),
-            elem_other.get_optimization_type()
-        );
+        elem.assert_same_params(elem_other);
 
-        let params = get_params(
-            TargetF::MODULUS_BIT_SIZE as usize,
-            BaseF::MODULUS_BIT_SIZE as usize,
-            elem.get_optimization_type(),
-        );
+        let params = elem.params;
 
         if 2 * params.bits_per_limb + ark_std::log2(params.num_limbs) as usize
             > BaseF::MODULUS_BIT_SIZE as usize - 1
@@ -164,12 +211,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> Reducer<TargetF, BaseF> {
         loop {
             let prod_of_num_of_additions = (elem.num_of_additions_over_normal_form + BaseF::one())
                 * (elem_other.num_of_additions_over_normal_form + BaseF::one());
-            let overhead_limb = overhead!(prod_of_num_of_additions.mul(
-                &BaseF::from_bigint(<BaseF as PrimeField>::BigInt::from(
-                    (params.num_limbs) as u64
-                ))
-                .unwrap()
-            ));
+            let overhead_limb = overhead_of_product(prod_of_num_of_additions, params.num_limbs);
             let bits_per_mulresult_limb = 2 * (params.bits_per_limb + 1) + overhead_limb;
 
             if bits_per_mulresult_limb < BaseF::MODULUS_BIT_SIZE as usize {
@@ -315,7 +357,7 @@ impl<TargetF: PrimeField, BaseF: PrimeField> Reducer<TargetF, BaseF> {
                     &accumulated_extra,
                 )))?;
             } else {
-                Reducer::<TargetF, BaseF>::limb_to_bits(&carry, surfeit + bits_per_limb)?;
+                carry.enforce_bit_length(surfeit + bits_per_limb)?;
             }
         }
 
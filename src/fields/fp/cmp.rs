@@ -1,8 +1,25 @@
-use crate::{fields::fp::FpVar, prelude::*};
+use crate::{cmp::CmpGadget, fields::fp::FpVar, prelude::*};
 use ark_ff::PrimeField;
 use ark_relations::gr1cs::SynthesisError;
 use core::cmp::Ordering;
 
+/// Compares `FpVar`s as canonical integers in `[0, p)`.
+///
+/// Unlike [`FpVar::is_cmp`], this does not require the operands to be at most
+/// `(p-1)/2`. Both operands are decomposed with the strict
+/// [`ToBitsGadget::to_bits_le`], so every field element is supported.
+impl<F: PrimeField> CmpGadget<F> for FpVar<F> {
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_ge(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        if let (FpVar::Constant(a), FpVar::Constant(b)) = (self, other) {
+            return Ok(Boolean::constant(a >= b));
+        }
+        let self_bits = self.to_bits_le()?;
+        let other_bits = other.to_bits_le()?;
+        Self::is_ge_from_bits_le(&self_bits, &other_bits)
+    }
+}
+
 impl<F: PrimeField> FpVar<F> {
     /// This function enforces the ordering between `self` and `other`. The
     /// constraint system will not be satisfied otherwise. If `self` should
@@ -72,6 +89,102 @@ impl<F: PrimeField> FpVar<F> {
         left.is_smaller_than_unchecked(&right)
     }
 
+    /// This function checks the ordering between `self` and `other`, which are
+    /// both required to be smaller than `2^bit_bound`. It outputs a `Boolean`
+    /// that contains the result - `1` if true, `0` otherwise. If `self` should
+    /// also be checked for equality, e.g. `self <= other` instead of `self <
+    /// other`, set `should_also_check_quality` to `true`. This variant
+    /// verifies `self` and `other` are `< 2^bit_bound`.
+    ///
+    /// For small `bit_bound` this is much cheaper than both [`Self::is_cmp`]
+    /// and the full-range [`CmpGadget`] implementation.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_cmp_with_bit_bound(
+        &self,
+        other: &FpVar<F>,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+        bit_bound: usize,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        self.enforce_bit_bound(bit_bound)?;
+        other.enforce_bit_bound(bit_bound)?;
+        self.is_cmp_with_bit_bound_unchecked(other, ordering, should_also_check_equality, bit_bound)
+    }
+
+    /// This function checks the ordering between `self` and `other`, which are
+    /// both assumed to be smaller than `2^bit_bound`. It outputs a `Boolean`
+    /// that contains the result - `1` if true, `0` otherwise. If `self` should
+    /// also be checked for equality, e.g. `self <= other` instead of `self <
+    /// other`, set `should_also_check_quality` to `true`. This variant does
+    /// not generate constraints to verify that `self` and `other` are `<
+    /// 2^bit_bound`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_cmp_with_bit_bound_unchecked(
+        &self,
+        other: &FpVar<F>,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+        bit_bound: usize,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        if bit_bound + 1 >= F::MODULUS_BIT_SIZE as usize {
+            // The bound does not buy us anything, so fall back to the
+            // full-range comparison.
+            return match (ordering, should_also_check_equality) {
+                (Ordering::Less, false) => self.is_lt(other),
+                (Ordering::Less, true) => self.is_le(other),
+                (Ordering::Greater, false) => self.is_gt(other),
+                (Ordering::Greater, true) => self.is_ge(other),
+                (Ordering::Equal, _) => Err(SynthesisError::Unsatisfiable),
+            };
+        }
+        let (left, right) = self.process_cmp_inputs(other, ordering, should_also_check_equality)?;
+        // `right` is at most `2^bit_bound` here, which `is_ge_with_bit_bound_unchecked`
+        // still handles correctly.
+        Ok(!left.is_ge_with_bit_bound_unchecked(&right, bit_bound)?)
+    }
+
+    /// This function enforces the ordering between `self` and `other`, which
+    /// are both required to be smaller than `2^bit_bound`. The constraint
+    /// system will not be satisfied otherwise. If `self` should also be
+    /// checked for equality, e.g. `self <= other` instead of `self < other`,
+    /// set `should_also_check_quality` to `true`. This variant verifies
+    /// `self` and `other` are `< 2^bit_bound`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_cmp_with_bit_bound(
+        &self,
+        other: &FpVar<F>,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+        bit_bound: usize,
+    ) -> Result<(), SynthesisError> {
+        self.is_cmp_with_bit_bound(other, ordering, should_also_check_equality, bit_bound)?
+            .enforce_equal(&Boolean::TRUE)
+    }
+
+    /// This function enforces the ordering between `self` and `other`, which
+    /// are both assumed to be smaller than `2^bit_bound`. The constraint
+    /// system will not be satisfied otherwise. If `self` should also be
+    /// checked for equality, e.g. `self <= other` instead of `self < other`,
+    /// set `should_also_check_quality` to `true`. This variant does not
+    /// generate constraints to verify that `self` and `other` are `<
+    /// 2^bit_bound`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_cmp_with_bit_bound_unchecked(
+        &self,
+        other: &FpVar<F>,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+        bit_bound: usize,
+    ) -> Result<(), SynthesisError> {
+        self.is_cmp_with_bit_bound_unchecked(
+            other,
+            ordering,
+            should_also_check_equality,
+            bit_bound,
+        )?
+        .enforce_equal(&Boolean::TRUE)
+    }
+
     fn process_cmp_inputs(
         &self,
         other: &Self,
@@ -106,6 +219,66 @@ impl<F: PrimeField> FpVar<F> {
         Ok(())
     }
 
+    /// Helper function to enforce that `self < 2^bit_bound`. Bounds that do not
+    /// fit below the modulus are trivially satisfied and generate no
+    /// constraints.
+    fn enforce_bit_bound(&self, bit_bound: usize) -> Result<(), SynthesisError> {
+        if bit_bound < F::MODULUS_BIT_SIZE as usize {
+            let _ = self.to_bits_le_with_top_bits_zero(bit_bound)?;
+        }
+        Ok(())
+    }
+
+    /// Helper function to check `self >= other` and output a result bit. This
+    /// function assumes `self` and `other` are `< 2^bit_bound` (`other` may
+    /// also be equal to `2^bit_bound`) and does not generate constraints to
+    /// verify that.
+    ///
+    /// Under these assumptions `self - other + 2^bit_bound` lies in `[0,
+    /// 2^(bit_bound + 1))`, and its top bit is set exactly when `self >=
+    /// other`.
+    fn is_ge_with_bit_bound_unchecked(
+        &self,
+        other: &FpVar<F>,
+        bit_bound: usize,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        let shift = F::from(2u8).pow([bit_bound as u64]);
+        let (bits, _) = (self - other + shift).to_bits_le_with_top_bits_zero(bit_bound + 1)?;
+        Ok(bits.last().unwrap().clone())
+    }
+
+    /// Helper function to check `a >= b`, where `a` and `b` are given by their
+    /// little-endian bit decompositions of equal length.
+    ///
+    /// The decompositions are split into chunks, which are small enough to be
+    /// compared with [`Self::is_ge_with_bit_bound_unchecked`]. The bits of an
+    /// `F` element need two chunks; longer decompositions, such as those of
+    /// emulated field elements, need more.
+    pub(crate) fn is_ge_from_bits_le(
+        a: &[Boolean<F>],
+        b: &[Boolean<F>],
+    ) -> Result<Boolean<F>, SynthesisError> {
+        assert_eq!(a.len(), b.len());
+        let max_chunk_size = F::MODULUS_BIT_SIZE as usize - 2;
+        let num_chunks = core::cmp::max(2, (a.len() + max_chunk_size - 1) / max_chunk_size);
+        let compare_chunk = |i: usize| {
+            let (start, end) = (i * a.len() / num_chunks, (i + 1) * a.len() / num_chunks);
+            let a_chunk = Boolean::le_bits_to_fp(&a[start..end])?;
+            let b_chunk = Boolean::le_bits_to_fp(&b[start..end])?;
+            let is_ge = a_chunk.is_ge_with_bit_bound_unchecked(&b_chunk, end - start)?;
+            Ok::<_, SynthesisError>((is_ge, a_chunk, b_chunk))
+        };
+
+        let (mut result, ..) = compare_chunk(0)?;
+        for i in 1..num_chunks {
+            let (chunk_is_ge, a_chunk, b_chunk) = compare_chunk(i)?;
+            // If the higher chunks agree, the lower chunks decide; otherwise
+            // the higher chunks do.
+            result = a_chunk.is_eq(&b_chunk)?.select(&result, &chunk_is_ge)?;
+        }
+        Ok(result)
+    }
+
     /// Helper function to check `self < other` and output a result bit. This
     /// function verifies `self` and `other` are `<= (p-1)/2`.
     fn is_smaller_than(&self, other: &FpVar<F>) -> Result<Boolean<F>, SynthesisError> {
@@ -147,7 +320,7 @@ impl<F: PrimeField> FpVar<F> {
 mod test {
     use ark_std::{cmp::Ordering, rand::Rng};
 
-    use crate::{alloc::AllocVar, fields::fp::FpVar};
+    use crate::{alloc::AllocVar, cmp::CmpGadget, fields::fp::FpVar, GR1CSVar};
     use ark_ff::{PrimeField, UniformRand};
     use ark_relations::gr1cs::ConstraintSystem;
     use ark_test_curves::bls12_381::Fr;
@@ -231,4 +404,100 @@ mod test {
             assert!(cs.is_satisfied().unwrap());
         }
     }
+
+    #[test]
+    fn test_cmp_full_range() {
+        let mut rng = ark_std::test_rng();
+        let minus_one = -Fr::from(1u8);
+        let half: Fr = Fr::MODULUS_MINUS_ONE_DIV_TWO.into();
+        let mut values = vec![
+            Fr::from(0u8),
+            Fr::from(1u8),
+            half,
+            half + Fr::from(1u8),
+            minus_one - Fr::from(1u8),
+            minus_one,
+        ];
+        values.extend((0..4).map(|_| Fr::rand(&mut rng)));
+
+        for &a in &values {
+            for &b in &values {
+                let cs = ConstraintSystem::<Fr>::new_ref();
+                let a_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(a)).unwrap();
+                let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(b)).unwrap();
+                assert_eq!(a_var.is_ge(&b_var).unwrap().value().unwrap(), a >= b);
+                assert_eq!(a_var.is_gt(&b_var).unwrap().value().unwrap(), a > b);
+                assert_eq!(a_var.is_le(&b_var).unwrap().value().unwrap(), a <= b);
+                assert_eq!(a_var.is_lt(&b_var).unwrap().value().unwrap(), a < b);
+                assert!(cs.is_satisfied().unwrap());
+
+                let b_const = FpVar::<Fr>::Constant(b);
+                assert_eq!(a_var.is_ge(&b_const).unwrap().value().unwrap(), a >= b);
+                assert_eq!(
+                    FpVar::Constant(a).is_ge(&b_const).unwrap().value().unwrap(),
+                    a >= b
+                );
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cmp_with_bit_bound() {
+        let mut rng = ark_std::test_rng();
+        let bit_bound = 16;
+        let max = (1u64 << bit_bound) - 1;
+        let mut values = vec![0u64, 1, max - 1, max];
+        values.extend((0..4).map(|_| rng.gen_range(0..=max)));
+
+        for &a in &values {
+            for &b in &values {
+                let cs = ConstraintSystem::<Fr>::new_ref();
+                let a_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(a))).unwrap();
+                let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(b))).unwrap();
+                for (ordering, check_eq, expected) in [
+                    (Ordering::Less, false, a < b),
+                    (Ordering::Less, true, a <= b),
+                    (Ordering::Greater, false, a > b),
+                    (Ordering::Greater, true, a >= b),
+                ] {
+                    let result = a_var
+                        .is_cmp_with_bit_bound(&b_var, ordering, check_eq, bit_bound)
+                        .unwrap();
+                    assert_eq!(result.value().unwrap(), expected);
+                    let result = a_var
+                        .is_cmp_with_bit_bound_unchecked(&b_var, ordering, check_eq, bit_bound)
+                        .unwrap();
+                    assert_eq!(result.value().unwrap(), expected);
+                }
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+
+        // Operands exceeding the bound make the checked variant unsatisfiable.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a_var =
+            FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(1u64 << bit_bound))).unwrap();
+        let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(0u8))).unwrap();
+        a_var
+            .enforce_cmp_with_bit_bound(&b_var, Ordering::Greater, false, bit_bound)
+            .unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        // A bound that does not fit below the modulus falls back to the
+        // full-range comparison.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = -Fr::from(1u8);
+        let a_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(0u8))).unwrap();
+        a_var
+            .enforce_cmp_with_bit_bound(
+                &b_var,
+                Ordering::Greater,
+                false,
+                Fr::MODULUS_BIT_SIZE as usize,
+            )
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
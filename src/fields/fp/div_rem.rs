@@ -0,0 +1,224 @@
+use crate::{cmp::CmpGadget, fields::fp::FpVar, prelude::*};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+impl<F: PrimeField> FpVar<F> {
+    /// Computes the quotient `q` and remainder `r` of the integer division of
+    /// `self` by the constant `d`, i.e. `self = q * d + r` with `0 <= r < d`.
+    ///
+    /// `self` is interpreted as an integer smaller than `2^max_bits`. `q` and
+    /// `r` are allocated as witnesses; `q` is range-checked to the bit length
+    /// of `(2^max_bits - 1) / d`, and `r < d` is enforced via [`CmpGadget`].
+    /// If `self >= 2^max_bits`, the constraint system may be unsatisfiable,
+    /// but it is never satisfied by an incorrect `q` or `r`.
+    ///
+    /// Returns `SynthesisError::DivisionByZero` if `d` is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bits >= F::MODULUS_BIT_SIZE`, or if `q * d + r` could
+    /// wrap around the modulus for the given bound.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn div_rem_by_constant(
+        &self,
+        d: F,
+        max_bits: usize,
+    ) -> Result<(Self, Self), SynthesisError> {
+        if d.is_zero() {
+            return Err(SynthesisError::DivisionByZero);
+        }
+        assert!(max_bits < F::MODULUS_BIT_SIZE as usize);
+        let d_int: BigUint = d.into();
+        let max_q = ((BigUint::one() << max_bits) - 1u8) / &d_int;
+        let q_bits = max_q.bits() as usize;
+        // For the range-checked `q` and `r`, `q * d + r` is at most `2^q_bits *
+        // d - 1`, which must be smaller than the modulus so that `self = q * d
+        // + r` holds over the integers.
+        let modulus: BigUint = F::MODULUS.into();
+        assert!((BigUint::one() << q_bits) * &d_int - 1u8 < modulus);
+
+        if let FpVar::Constant(x) = self {
+            let x: BigUint = (*x).into();
+            let (q, r) = (&x / &d_int, x % &d_int);
+            return Ok((FpVar::Constant(q.into()), FpVar::Constant(r.into())));
+        }
+
+        let (q, r) = self.new_div_rem_witnesses(|| Ok(d))?;
+        let _ = q.to_bits_le_with_top_bits_zero(q_bits)?;
+        r.is_lt(&FpVar::Constant(d))?
+            .enforce_equal(&Boolean::TRUE)?;
+        (&q * d + &r).enforce_equal(self)?;
+        Ok((q, r))
+    }
+
+    /// Computes the quotient `q` and remainder `r` of the integer division of
+    /// `self` by `d`, i.e. `self = q * d + r` with `0 <= r < d`.
+    ///
+    /// `self` and `d` are interpreted as integers smaller than `2^max_bits`.
+    /// `q` and `r` are allocated as witnesses; `q` and `d` are range-checked
+    /// to `max_bits` bits, and `r < d` is enforced via [`CmpGadget`]. In
+    /// particular, the constraint system is unsatisfiable if `d` is zero or
+    /// `d >= 2^max_bits`. If `self >= 2^max_bits`, the constraint system may
+    /// be unsatisfiable, but it is never satisfied by an incorrect `q` or
+    /// `r`.
+    ///
+    /// If `d` is a constant, this is equivalent to
+    /// [`Self::div_rem_by_constant`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `2 * max_bits >= F::MODULUS_BIT_SIZE`, as `q * d + r` could
+    /// then wrap around the modulus.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn div_rem(&self, d: &Self, max_bits: usize) -> Result<(Self, Self), SynthesisError> {
+        if let FpVar::Constant(d) = d {
+            return self.div_rem_by_constant(*d, max_bits);
+        }
+        // `q * d + r < 2^max_bits * 2^max_bits <= 2^(MODULUS_BIT_SIZE - 1) < p`.
+        assert!(2 * max_bits < F::MODULUS_BIT_SIZE as usize);
+
+        let (q, r) = self.new_div_rem_witnesses(|| d.value())?;
+        let _ = q.to_bits_le_with_top_bits_zero(max_bits)?;
+        let _ = d.to_bits_le_with_top_bits_zero(max_bits)?;
+        r.is_lt(d)?.enforce_equal(&Boolean::TRUE)?;
+        q.mul_equals(d, &(self - &r))?;
+        Ok((q, r))
+    }
+
+    /// Allocates the quotient and remainder of the integer division of `self`
+    /// by the value returned by `d` as witnesses, without generating any
+    /// constraints. Division by zero yields zero for both.
+    fn new_div_rem_witnesses(
+        &self,
+        d: impl Fn() -> Result<F, SynthesisError>,
+    ) -> Result<(Self, Self), SynthesisError> {
+        let cs = self.cs();
+        let div_rem = || -> Result<(BigUint, BigUint), SynthesisError> {
+            let x: BigUint = self.value()?.into();
+            let d: BigUint = d()?.into();
+            if d.is_zero() {
+                Ok((BigUint::zero(), BigUint::zero()))
+            } else {
+                Ok((&x / &d, x % d))
+            }
+        };
+        let q = FpVar::new_witness(ark_relations::ns!(cs, "quotient"), || {
+            div_rem().map(|(q, _)| F::from(q))
+        })?;
+        let r = FpVar::new_witness(ark_relations::ns!(cs, "remainder"), || {
+            div_rem().map(|(_, r)| F::from(r))
+        })?;
+        Ok((q, r))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar, GR1CSVar};
+    use ark_ff::{BigInteger, PrimeField, UniformRand};
+    use ark_relations::gr1cs::{ConstraintSystem, SynthesisError};
+    use ark_test_curves::bls12_381::Fr;
+    use num_bigint::BigUint;
+
+    fn to_int(f: Fr) -> BigUint {
+        f.into()
+    }
+
+    /// Returns a random field element smaller than `2^bits`.
+    fn rand_bounded(rng: &mut impl ark_std::rand::Rng, bits: usize) -> Fr {
+        let mut bigint = Fr::rand(rng).into_bigint();
+        bigint.divn(Fr::MODULUS_BIT_SIZE - bits as u32);
+        Fr::from_bigint(bigint).unwrap()
+    }
+
+    fn check(x: Fr, d: Fr, max_bits: usize, constant_divisor: bool) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+        let (q, r) = if constant_divisor {
+            x_var.div_rem_by_constant(d, max_bits).unwrap()
+        } else {
+            let d_var = FpVar::new_witness(cs.clone(), || Ok(d)).unwrap();
+            x_var.div_rem(&d_var, max_bits).unwrap()
+        };
+        assert_eq!(to_int(q.value().unwrap()), to_int(x) / to_int(d));
+        assert_eq!(to_int(r.value().unwrap()), to_int(x) % to_int(d));
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_div_rem_by_constant() {
+        let mut rng = ark_std::test_rng();
+        // The largest supported bound, so that `x` comes close to the modulus.
+        let max_bits = Fr::MODULUS_BIT_SIZE as usize - 2;
+        let max = Fr::from(BigUint::from(1u8) << max_bits) - Fr::from(1u8);
+        for d in [Fr::from(1u8), Fr::from(2u8), Fr::from(255u8), max] {
+            for x in [
+                Fr::from(0u8),
+                d - Fr::from(1u8),
+                d,
+                max - Fr::from(1u8),
+                max,
+            ] {
+                check(x, d, max_bits, true);
+            }
+        }
+        for _ in 0..10 {
+            let d = rand_bounded(&mut rng, 64) + Fr::from(1u8);
+            let x = rand_bounded(&mut rng, max_bits);
+            check(x, d, max_bits, true);
+        }
+
+        // Constant inputs produce constant outputs.
+        let (q, r) = FpVar::Constant(Fr::from(17u8))
+            .div_rem_by_constant(Fr::from(5u8), 8)
+            .unwrap();
+        assert_eq!(
+            (q.value().unwrap(), r.value().unwrap()),
+            (Fr::from(3u8), Fr::from(2u8))
+        );
+
+        assert!(matches!(
+            FpVar::Constant(Fr::from(1u8)).div_rem_by_constant(Fr::from(0u8), 8),
+            Err(SynthesisError::DivisionByZero)
+        ));
+
+        // A dividend exceeding the bound, close to the modulus, is rejected.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(-Fr::from(1u8))).unwrap();
+        let _ = x_var.div_rem_by_constant(Fr::from(3u8), 64).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut rng = ark_std::test_rng();
+        let max_bits = (Fr::MODULUS_BIT_SIZE as usize - 1) / 2;
+        let max = Fr::from(BigUint::from(1u8) << max_bits) - Fr::from(1u8);
+        for d in [Fr::from(1u8), Fr::from(2u8), max] {
+            for x in [Fr::from(0u8), d - Fr::from(1u8), d, max] {
+                check(x, d, max_bits, false);
+            }
+        }
+        for _ in 0..10 {
+            let d = rand_bounded(&mut rng, max_bits / 2) + Fr::from(1u8);
+            let x = rand_bounded(&mut rng, max_bits);
+            check(x, d, max_bits, false);
+        }
+
+        // Division by zero is unsatisfiable.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(7u8))).unwrap();
+        let d_var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u8))).unwrap();
+        let _ = x_var.div_rem(&d_var, max_bits).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        // So is a divisor exceeding the bound.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(7u8))).unwrap();
+        let d_var = FpVar::new_witness(cs.clone(), || Ok(-Fr::from(1u8))).unwrap();
+        let _ = x_var.div_rem(&d_var, max_bits).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
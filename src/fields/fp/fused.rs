@@ -0,0 +1,87 @@
+use super::{predicate, AllocatedFp, FpVar};
+use crate::{prelude::*, Assignment};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::{LinearCombination, SynthesisError, Variable};
+
+impl<F: PrimeField> FpVar<F> {
+    /// Computes `self * b + c`.
+    ///
+    /// This is free if `self` or `b` is a constant, and otherwise costs a
+    /// single constraint: one with the predicate [`predicate::MUL_ADD`] if it
+    /// is registered in the constraint system (see
+    /// [`predicate::register_mul_add`]), or else the R1CS constraint
+    /// `self * b = result - c`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn mul_add(&self, b: &Self, c: &Self) -> Result<Self, SynthesisError> {
+        if self.is_constant() || b.is_constant() {
+            return Ok(self * b + c);
+        }
+        let cs = self.cs().or(b.cs()).or(c.cs());
+        let result =
+            AllocatedFp::new_witness(cs.clone(), || Ok(self.value()? * b.value()? + c.value()?))?;
+        if predicate::is_registered(&cs, predicate::MUL_ADD) {
+            cs.enforce_constraint(
+                predicate::MUL_ADD,
+                vec![self.lc(), b.lc(), c.lc(), lc!() + result.variable],
+            )?;
+        } else {
+            cs.enforce_r1cs_constraint(self.lc(), b.lc(), lc!() + result.variable - c.lc())?;
+        }
+        Ok(Self::Var(result))
+    }
+
+    /// Computes `self * b * c`.
+    ///
+    /// If any of the factors is a constant, this costs at most one
+    /// constraint. Otherwise, it costs a single constraint with the predicate
+    /// [`predicate::MUL3`] if it is registered in the constraint system (see
+    /// [`predicate::register_mul3`]), or else two R1CS constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn mul3(&self, b: &Self, c: &Self) -> Result<Self, SynthesisError> {
+        let cs = self.cs().or(b.cs()).or(c.cs());
+        let all_variables = !(self.is_constant() || b.is_constant() || c.is_constant());
+        if !all_variables || !predicate::is_registered(&cs, predicate::MUL3) {
+            return Ok(self * b * c);
+        }
+        let result =
+            AllocatedFp::new_witness(cs.clone(), || Ok(self.value()? * b.value()? * c.value()?))?;
+        cs.enforce_constraint(
+            predicate::MUL3,
+            vec![self.lc(), b.lc(), c.lc(), lc!() + result.variable],
+        )?;
+        Ok(Self::Var(result))
+    }
+
+    /// Computes `self^5`.
+    ///
+    /// This costs a single constraint with the predicate [`predicate::POW5`]
+    /// if it is registered in the constraint system (see
+    /// [`predicate::register_pow5`]), or else three R1CS constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn pow5(&self) -> Result<Self, SynthesisError> {
+        match self {
+            Self::Constant(c) => Ok(Self::Constant(c.pow([5]))),
+            Self::Var(v) => {
+                if !predicate::is_registered(&v.cs, predicate::POW5) {
+                    let square = self.square()?;
+                    return Ok(square.square()? * self);
+                }
+                let result =
+                    AllocatedFp::new_witness(v.cs.clone(), || Ok(v.value.get()?.pow([5])))?;
+                v.cs.enforce_constraint(
+                    predicate::POW5,
+                    vec![lc!() + v.variable, lc!() + result.variable],
+                )?;
+                Ok(Self::Var(result))
+            },
+        }
+    }
+
+    /// Returns the linear combination corresponding to `self`.
+    fn lc(&self) -> LinearCombination<F> {
+        match self {
+            Self::Constant(c) => lc!() + (*c, Variable::One),
+            Self::Var(v) => lc!() + v.variable,
+        }
+    }
+}
@@ -1,14 +1,25 @@
 use ark_ff::{BigInteger, PrimeField};
 use ark_relations::gr1cs::{
-    ConstraintSystemRef, LinearCombination, Namespace, SynthesisError, Variable,
+    ConstraintSystemRef, LinearCombination, Namespace, OptimizationGoal, SynthesisError, Variable,
 };
 
 use core::borrow::Borrow;
 
-use crate::{boolean::AllocatedBool, convert::ToConstraintFieldGadget, prelude::*, Assignment};
+use crate::{
+    boolean::AllocatedBool, convert::ToConstraintFieldGadget, prelude::*,
+    select::select_power_of_two_tree, Assignment,
+};
 use ark_std::{iter::Sum, vec::Vec};
 
 mod cmp;
+mod div_rem;
+mod fused;
+mod range_check;
+mod set;
+
+/// Custom GR1CS predicates that `FpVar` gadgets can use in place of plain R1CS
+/// constraints, once they are registered in the constraint system.
+pub mod predicate;
 
 /// Represents a variable in the constraint system whose
 /// value can be an arbitrary field element.
@@ -73,6 +84,116 @@ impl<F: PrimeField> FpVar<F> {
         rest.enforce_equal(&Self::zero())?;
         Ok((lower_bits, rest))
     }
+
+    /// Computes the inner product `sum_i a[i] * b[i]`.
+    ///
+    /// See [`Self::sum_of_products`] for the cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b))]
+    pub fn inner_product(a: &[Self], b: &[Self]) -> Result<Self, SynthesisError> {
+        Self::sum_of_products(a, b, None)
+    }
+
+    /// Computes `sum_i coeffs[i] * a[i] * b[i]`, where `coeffs` defaults to
+    /// all ones.
+    ///
+    /// Products with a constant factor are free. The remaining `n` products
+    /// are checked with a single constraint if the predicate
+    /// [`predicate::sum_of_products_label(n)`](predicate::sum_of_products_label)
+    /// is registered in the constraint system (see
+    /// [`predicate::register_sum_of_products`]). Otherwise, `n` R1CS
+    /// constraints are generated, allocating one variable per product and
+    /// none for the sum.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a`, `b` and `coeffs` (if given) have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b, coeffs))]
+    pub fn sum_of_products(
+        a: &[Self],
+        b: &[Self],
+        coeffs: Option<&[F]>,
+    ) -> Result<Self, SynthesisError> {
+        assert_eq!(a.len(), b.len());
+        if let Some(coeffs) = coeffs {
+            assert_eq!(a.len(), coeffs.len());
+        }
+        let coeff = |i: usize| coeffs.map_or(F::one(), |c| c[i]);
+
+        // Products involving a constant are linear, and so are accumulated
+        // without any constraints.
+        let mut linear = Self::zero();
+        let mut products = Vec::new();
+        for (i, (a, b)) in a.iter().zip(b).enumerate() {
+            let c = coeff(i);
+            if c.is_zero() {
+                continue;
+            }
+            match (a, b) {
+                (Self::Var(a), Self::Var(b)) => products.push((a, b, c)),
+                _ => linear += a * b * c,
+            }
+        }
+        if products.is_empty() {
+            return Ok(linear);
+        }
+
+        let cs = products
+            .iter()
+            .fold(ConstraintSystemRef::None, |cs, (a, b, _)| {
+                cs.or(a.cs.clone()).or(b.cs.clone())
+            });
+        let product_value =
+            |(a, b, c): &(&AllocatedFp<F>, &AllocatedFp<F>, F)| -> Result<F, SynthesisError> {
+                Ok(a.value.get()? * b.value.get()? * c)
+            };
+
+        let n = products.len();
+        let label = predicate::sum_of_products_label(n);
+        let result = if predicate::is_registered(&cs, &label) {
+            let result = AllocatedFp::new_witness(cs.clone(), || {
+                products.iter().map(product_value).sum::<Result<F, _>>()
+            })?;
+            let mut lcs = Vec::with_capacity(2 * n + 1);
+            for (a, b, c) in &products {
+                lcs.push(lc!() + a.variable);
+                lcs.push(lc!() + (*c, b.variable));
+            }
+            lcs.push(lc!() + result.variable);
+            cs.enforce_constraint(&label, lcs)?;
+            result
+        } else {
+            // Allocate all but the last product, and fold the sum into the
+            // constraint for the last one:
+            // `a_{n-1} * (c_{n-1} * b_{n-1}) = result - sum_{i < n-1} p_i`.
+            let (last, rest) = products.split_last().unwrap();
+            let mut partial_sum = lc!();
+            for product in rest {
+                let (a, b, c) = product;
+                let p = AllocatedFp::new_witness(cs.clone(), || product_value(product))?;
+                cs.enforce_r1cs_constraint(
+                    lc!() + a.variable,
+                    lc!() + (*c, b.variable),
+                    lc!() + p.variable,
+                )?;
+                partial_sum = partial_sum + p.variable;
+            }
+            let result = AllocatedFp::new_witness(cs.clone(), || {
+                products.iter().map(product_value).sum::<Result<F, _>>()
+            })?;
+            let (a, b, c) = last;
+            cs.enforce_r1cs_constraint(
+                lc!() + a.variable,
+                lc!() + (*c, b.variable),
+                lc!() + result.variable - partial_sum,
+            )?;
+            result
+        };
+        Ok(Self::Var(result) + linear)
+    }
 }
 
 impl<F: PrimeField> GR1CSVar<F> for FpVar<F> {
@@ -352,9 +473,24 @@ impl<F: PrimeField> AllocatedFp<F> {
         )
     }
 
+    /// Allocates a new variable that is equal to `self`.
+    ///
+    /// This requires one constraint, and is used to write out the linear
+    /// combination behind `self` only once when it would otherwise be copied
+    /// into several constraints.
+    fn materialize(&self) -> Result<Self, SynthesisError> {
+        let result = Self::new_witness(self.cs.clone(), || self.value.get())?;
+        self.cs.enforce_r1cs_constraint(
+            lc!() + self.variable,
+            lc!() + Variable::One,
+            lc!() + result.variable,
+        )?;
+        Ok(result)
+    }
+
     /// Outputs the bit `self == other`.
     ///
-    /// This requires two constraints.
+    /// See [`Self::is_neq`] for the cost.
     #[tracing::instrument(target = "gr1cs")]
     pub fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
         Ok(!self.is_neq(other)?)
@@ -362,9 +498,31 @@ impl<F: PrimeField> AllocatedFp<F> {
 
     /// Outputs the bit `self != other`.
     ///
-    /// This requires two constraints.
+    /// This requires two constraints, in both of which `self - other` appears.
+    /// If the optimization goal of the constraint system is
+    /// `OptimizationGoal::Weight` and `self` or `other` is a linear
+    /// combination of several variables, `self - other` is first allocated as
+    /// a new variable, using a third constraint, so that the linear
+    /// combination is only written out once.
     #[tracing::instrument(target = "gr1cs")]
     pub fn is_neq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        let materialize_difference = self.cs.optimization_goal() == OptimizationGoal::Weight
+            && (self.variable.is_lc() || other.variable.is_lc());
+        self.is_neq_with(other, materialize_difference)
+    }
+
+    /// Outputs the bit `self != other`, allocating `self - other` as a new
+    /// variable if `materialize_difference` is set.
+    fn is_neq_with(
+        &self,
+        other: &Self,
+        materialize_difference: bool,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        let difference = if materialize_difference {
+            lc!() + self.sub(other).materialize()?.variable
+        } else {
+            lc!() + self.variable - other.variable
+        };
         // We don't need to enforce `is_not_equal` to be boolean here;
         // see the comments above the constraints below for why.
         let is_not_equal = Boolean::from(AllocatedBool::new_witness_without_booleanity_check(
@@ -426,15 +584,12 @@ impl<F: PrimeField> AllocatedFp<F> {
         // Since these are the only possible two cases, `is_not_equal` is always
         // constrained to 0 or 1.
         self.cs.enforce_r1cs_constraint(
-            lc!() + self.variable - other.variable,
+            difference.clone(),
             lc!() + multiplier,
             is_not_equal.lc(),
         )?;
-        self.cs.enforce_r1cs_constraint(
-            lc!() + self.variable - other.variable,
-            (!&is_not_equal).lc(),
-            lc!(),
-        )?;
+        self.cs
+            .enforce_r1cs_constraint(difference, (!&is_not_equal).lc(), lc!())?;
         Ok(is_not_equal)
     }
 
@@ -500,6 +655,11 @@ impl<F: PrimeField> ToBitsGadget<F> for AllocatedFp<F> {
     ///
     /// This method enforces that the output is in the field, i.e.
     /// it invokes `Boolean::enforce_in_field_le` on the bit decomposition.
+    ///
+    /// The decomposition does not depend on the optimization goal: the
+    /// booleanity constraints contain no linear combinations to allocate, and
+    /// the recomposition constraint already writes out each bit once. Only
+    /// the in-field check follows the goal, through `Boolean::kary_and`.
     #[tracing::instrument(target = "gr1cs")]
     fn to_bits_le(&self) -> Result<Vec<Boolean<F>>, SynthesisError> {
         let bits = self.to_non_unique_bits_le()?;
@@ -893,8 +1053,12 @@ impl<F: PrimeField> EqGadget<F> for FpVar<F> {
             (Self::Constant(c1), Self::Constant(c2)) => Ok(Boolean::Constant(c1 == c2)),
             (Self::Constant(c), Self::Var(v)) | (Self::Var(v), Self::Constant(c)) => {
                 let cs = v.cs.clone();
+                // Only the non-constant side can make the difference a long
+                // linear combination.
+                let materialize_difference =
+                    cs.optimization_goal() == OptimizationGoal::Weight && v.variable.is_lc();
                 let c = AllocatedFp::new_constant(cs, c)?;
-                c.is_eq(v)
+                Ok(!c.is_neq_with(v, materialize_difference)?)
             },
             (Self::Var(v1), Self::Var(v2)) => v1.is_eq(v2),
         }
@@ -1022,6 +1186,41 @@ impl<F: PrimeField> CondSelectGadget<F> for FpVar<F> {
             },
         }
     }
+
+    /// Selects the element of `values` whose index is represented by the
+    /// big-endian bits in `position`, using a tree of `values.len() - 1`
+    /// conditional selections.
+    ///
+    /// In the first layer of the tree, each value at an even index is the
+    /// `false` case of a selection, and appears in both sides of its
+    /// constraint. If the optimization goal of the constraint system is
+    /// `OptimizationGoal::Weight`, such values that are linear combinations of
+    /// several variables are first allocated as new variables, using one
+    /// constraint each, so that they are only written out once.
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_select_power_of_two_vector(
+        position: &[Boolean<F>],
+        values: &[Self],
+    ) -> Result<Self, SynthesisError> {
+        let materialize = match position.last() {
+            Some(bit) => {
+                !bit.is_constant() && bit.cs().optimization_goal() == OptimizationGoal::Weight
+            },
+            None => false,
+        };
+        if !materialize {
+            return select_power_of_two_tree(position, values);
+        }
+        let values = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| match v {
+                Self::Var(v) if i % 2 == 0 && v.variable.is_lc() => v.materialize().map(Self::Var),
+                _ => Ok(v.clone()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        select_power_of_two_tree(position, &values)
+    }
 }
 
 /// Uses two bits to perform a lookup into a table
@@ -1137,11 +1336,18 @@ impl<'a, F: PrimeField> Sum<FpVar<F>> for FpVar<F> {
 mod test {
     use crate::{
         alloc::{AllocVar, AllocationMode},
+        boolean::Boolean,
         eq::EqGadget,
-        fields::fp::FpVar,
+        fields::{
+            fp::{predicate, FpVar},
+            FieldVar,
+        },
+        select::CondSelectGadget,
+        test_utils::{constraints_added, cost_with_goal},
         GR1CSVar,
     };
-    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_ff::Field;
+    use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, OptimizationGoal};
     use ark_std::{UniformRand, Zero};
     use ark_test_curves::bls12_381::Fr;
 
@@ -1175,4 +1381,174 @@ mod test {
         assert!(cs.is_satisfied().unwrap());
         assert_eq!(sum.value().unwrap(), sum_expected);
     }
+
+    #[test]
+    fn test_sum_of_products() {
+        let mut rng = ark_std::test_rng();
+        let n = 10;
+        let a = (0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let b = (0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let coeffs = (0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let inner_product: Fr = a.iter().zip(&b).map(|(a, b)| *a * b).sum();
+        let sum_of_products: Fr = a
+            .iter()
+            .zip(&b)
+            .zip(&coeffs)
+            .map(|((a, b), c)| *a * b * c)
+            .sum();
+
+        for use_predicate in [false, true] {
+            let cs = ConstraintSystem::new_ref();
+            if use_predicate {
+                predicate::register_sum_of_products(&cs, n).unwrap();
+            }
+            let a_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(a.clone())).unwrap();
+            let b_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(b.clone())).unwrap();
+
+            let (result, num_constraints) =
+                constraints_added(&cs, || FpVar::inner_product(&a_var, &b_var).unwrap());
+            assert_eq!(result.value().unwrap(), inner_product);
+            let expected_constraints = if use_predicate { 1 } else { n };
+            assert_eq!(num_constraints, expected_constraints);
+
+            let result = FpVar::sum_of_products(&a_var, &b_var, Some(&coeffs)).unwrap();
+            assert_eq!(result.value().unwrap(), sum_of_products);
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // Products with a constant factor do not need any constraints.
+        let cs = ConstraintSystem::new_ref();
+        let a_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(a.clone())).unwrap();
+        let b_var = b.iter().map(|b| FpVar::Constant(*b)).collect::<Vec<_>>();
+        let result = FpVar::inner_product(&a_var, &b_var).unwrap();
+        assert_eq!(result.value().unwrap(), inner_product);
+        assert_eq!(cs.num_constraints(), 0);
+
+        // The result is bound to the inputs.
+        let cs = ConstraintSystem::new_ref();
+        let a_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(a.clone())).unwrap();
+        let b_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(b.clone())).unwrap();
+        let result = FpVar::inner_product(&a_var, &b_var).unwrap();
+        result
+            .enforce_equal(&FpVar::Constant(inner_product + Fr::from(1u8)))
+            .unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_fused_gates() {
+        let mut rng = ark_std::test_rng();
+        let (a, b, c) = (Fr::rand(&mut rng), Fr::rand(&mut rng), Fr::rand(&mut rng));
+
+        for use_predicate in [false, true] {
+            let cs = ConstraintSystem::new_ref();
+            if use_predicate {
+                predicate::register_mul_add(&cs).unwrap();
+                predicate::register_mul3(&cs).unwrap();
+                predicate::register_pow5(&cs).unwrap();
+            }
+            let a_var = FpVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+            let b_var = FpVar::new_witness(cs.clone(), || Ok(b)).unwrap();
+            let c_var = FpVar::new_witness(cs.clone(), || Ok(c)).unwrap();
+
+            let (result, num_constraints) =
+                constraints_added(&cs, || a_var.mul_add(&b_var, &c_var).unwrap());
+            assert_eq!(result.value().unwrap(), a * b + c);
+            assert_eq!(num_constraints, 1);
+
+            let (result, num_constraints) =
+                constraints_added(&cs, || a_var.mul3(&b_var, &c_var).unwrap());
+            assert_eq!(result.value().unwrap(), a * b * c);
+            let expected_constraints = if use_predicate { 1 } else { 2 };
+            assert_eq!(num_constraints, expected_constraints);
+
+            let (result, num_constraints) = constraints_added(&cs, || a_var.pow5().unwrap());
+            assert_eq!(result.value().unwrap(), a.pow([5]));
+            let expected_constraints = if use_predicate { 1 } else { 3 };
+            assert_eq!(num_constraints, expected_constraints);
+            assert!(cs.is_satisfied().unwrap());
+
+            // Constant operands are handled without the predicates.
+            let c_const = FpVar::Constant(c);
+            let result = a_var.mul_add(&c_const, &b_var).unwrap();
+            assert_eq!(result.value().unwrap(), a * c + b);
+            let result = a_var.mul3(&b_var, &c_const).unwrap();
+            assert_eq!(result.value().unwrap(), a * b * c);
+            assert_eq!(c_const.pow5().unwrap().value().unwrap(), c.pow([5]));
+            assert!(cs.is_satisfied().unwrap());
+
+            // The results are bound to the inputs.
+            let result = a_var.pow5().unwrap();
+            result.enforce_equal(&FpVar::Constant(a)).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    /// Allocates `n` witnesses and returns their sum, which is a linear
+    /// combination of `n` variables. The witnesses are drawn from a fixed
+    /// seed, so sums of the same length have the same value.
+    fn new_witness_sum(cs: ConstraintSystemRef<Fr>, n: usize) -> FpVar<Fr> {
+        let mut rng = ark_std::test_rng();
+        (0..n)
+            .map(|_| FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap())
+            .sum()
+    }
+
+    #[test]
+    fn test_optimization_goal() {
+        let compare = |circuit: &dyn Fn(ConstraintSystemRef<Fr>)| {
+            let constraints = cost_with_goal(OptimizationGoal::Constraints, circuit);
+            let weight = cost_with_goal(OptimizationGoal::Weight, circuit);
+            assert!(constraints.0 < weight.0);
+            assert!(weight.1 < constraints.1);
+        };
+
+        // `is_eq` allocates the difference of long linear combinations.
+        compare(&|cs| {
+            let a = new_witness_sum(cs.clone(), 8);
+            let b = new_witness_sum(cs.clone(), 8);
+            let c = new_witness_sum(cs.clone(), 7);
+            assert!(a.is_eq(&b).unwrap().value().unwrap());
+            assert!(!a.is_eq(&c).unwrap().value().unwrap());
+        });
+
+        // The power-of-two selection allocates the long linear combinations
+        // that appear twice in the first layer of the tree.
+        compare(&|cs| {
+            let values = (0..4)
+                .map(|_| new_witness_sum(cs.clone(), 8))
+                .collect::<Vec<_>>();
+            let position =
+                [true, false].map(|b| Boolean::new_witness(cs.clone(), || Ok(b)).unwrap());
+            let result =
+                FpVar::conditionally_select_power_of_two_vector(&position, &values).unwrap();
+            assert_eq!(result.value().unwrap(), values[2].value().unwrap());
+        });
+    }
+
+    #[test]
+    fn test_to_bits_optimization_goal() {
+        let mut rng = ark_std::test_rng();
+        let value = Fr::rand(&mut rng);
+
+        // The decomposition itself has a single formulation.
+        let non_unique = |cs: ConstraintSystemRef<Fr>| {
+            let v = FpVar::new_witness(cs, || Ok(value)).unwrap();
+            v.to_non_unique_bits_le().unwrap();
+        };
+        assert_eq!(
+            cost_with_goal(OptimizationGoal::Constraints, non_unique),
+            cost_with_goal(OptimizationGoal::Weight, non_unique),
+        );
+
+        // The in-field check trades constraints for weight in `kary_and`.
+        let unique = |cs: ConstraintSystemRef<Fr>| {
+            let v = FpVar::new_witness(cs, || Ok(value)).unwrap();
+            v.to_bits_le().unwrap();
+        };
+        let constraints = cost_with_goal(OptimizationGoal::Constraints, unique);
+        let weight = cost_with_goal(OptimizationGoal::Weight, unique);
+        assert!(constraints.0 <= weight.0);
+        assert!(weight.1 <= constraints.1);
+    }
 }
@@ -0,0 +1,152 @@
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::{
+    predicate::PredicateConstraintSystem, ConstraintSystemRef, SynthesisError,
+};
+use ark_std::{string::String, vec::Vec};
+
+/// Returns the label of the degree-2 predicate
+/// `x_0 * x_1 + x_2 * x_3 + ... + x_{2n-2} * x_{2n-1} = x_{2n}`
+/// over `2n + 1` variables, which is used by [`super::FpVar::sum_of_products`]
+/// for `n` products.
+pub fn sum_of_products_label(n: usize) -> String {
+    ark_std::format!("SUM_OF_PRODUCTS_{n}")
+}
+
+/// Registers the predicate labelled by [`sum_of_products_label`] for `n`
+/// products in `cs`.
+///
+/// Once registered, [`super::FpVar::sum_of_products`] checks a sum of `n`
+/// non-constant products with a single constraint and no intermediate
+/// variables.
+pub fn register_sum_of_products<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    n: usize,
+) -> Result<(), SynthesisError> {
+    assert!(n > 0);
+    let mut terms: Vec<(F, Vec<(usize, usize)>)> = (0..n)
+        .map(|i| (F::one(), vec![(2 * i, 1), (2 * i + 1, 1)]))
+        .collect();
+    terms.push((-F::one(), vec![(2 * n, 1)]));
+    register(cs, &sum_of_products_label(n), 2 * n + 1, terms)
+}
+
+/// The label of the degree-2 predicate `x_0 * x_1 + x_2 = x_3`, which is used
+/// by [`super::FpVar::mul_add`].
+pub const MUL_ADD: &str = "MUL_ADD";
+
+/// Registers the predicate labelled by [`MUL_ADD`] in `cs`.
+pub fn register_mul_add<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+    let terms = vec![
+        (F::one(), vec![(0, 1), (1, 1)]),
+        (F::one(), vec![(2, 1)]),
+        (-F::one(), vec![(3, 1)]),
+    ];
+    register(cs, MUL_ADD, 4, terms)
+}
+
+/// The label of the degree-3 predicate `x_0 * x_1 * x_2 = x_3`, which is used
+/// by [`super::FpVar::mul3`].
+pub const MUL3: &str = "MUL3";
+
+/// Registers the predicate labelled by [`MUL3`] in `cs`.
+///
+/// Once registered, [`super::FpVar::mul3`] multiplies three variables with a
+/// single constraint instead of two.
+pub fn register_mul3<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+    let terms = vec![
+        (F::one(), vec![(0, 1), (1, 1), (2, 1)]),
+        (-F::one(), vec![(3, 1)]),
+    ];
+    register(cs, MUL3, 4, terms)
+}
+
+/// The label of the degree-5 predicate `x_0^5 = x_1`, which is used by
+/// [`super::FpVar::pow5`].
+pub const POW5: &str = "POW5";
+
+/// Registers the predicate labelled by [`POW5`] in `cs`.
+///
+/// Once registered, [`super::FpVar::pow5`] checks a fifth power, as used in
+/// the S-boxes of Poseidon-like hashes, with a single constraint and no
+/// intermediate variables instead of three constraints.
+pub fn register_pow5<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+    let terms = vec![(F::one(), vec![(0, 5)]), (-F::one(), vec![(1, 1)])];
+    register(cs, POW5, 2, terms)
+}
+
+/// Registers the polynomial predicate `sum_i c_i * prod_j x_j^e_j = 0` with
+/// the given `arity` and `terms` in `cs` under `label`, unless it is
+/// already present.
+fn register<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    label: &str,
+    arity: usize,
+    terms: Vec<(F, Vec<(usize, usize)>)>,
+) -> Result<(), SynthesisError> {
+    if cs.is_none() || is_registered(cs, label) {
+        return Ok(());
+    }
+    cs.register_predicate(
+        label,
+        PredicateConstraintSystem::new_polynomial_predicate_cs(arity, terms),
+    )
+}
+
+/// Returns whether a predicate labelled `label` has been registered in `cs`.
+///
+/// Gadgets use this to decide between a custom predicate and the equivalent
+/// plain R1CS constraints, so that constraint systems which only support R1CS
+/// are never handed custom predicates.
+pub fn is_registered<F: PrimeField>(cs: &ConstraintSystemRef<F>, label: &str) -> bool {
+    match cs {
+        ConstraintSystemRef::None => false,
+        ConstraintSystemRef::CS(r) => r.borrow().has_predicate(label),
+    }
+}
+
+/// Returns the label of the predicate `x * (x - 1) * ... * (x - (2^bits - 1)) =
+/// 0` over a single variable, which checks that `x` lies in the table `[0,
+/// 2^bits)`.
+pub fn range_label(bits: usize) -> String {
+    ark_std::format!("RANGE_{bits}")
+}
+
+/// The largest window for which [`register_range`] registers a predicate.
+///
+/// The range predicate has degree `2^bits`, which the prover has to handle
+/// for every check, so wider windows are not worth it.
+pub const MAX_RANGE_BITS: usize = 8;
+
+/// Registers the predicate labelled by [`range_label`] for `bits` in `cs`.
+///
+/// Once registered, [`crate::range_check::RangeCheckStrategy::Windowed`]
+/// checks each window of `bits` bits with a single constraint of degree
+/// `2^bits`.
+///
+/// # Panics
+///
+/// Panics if `bits` is zero or larger than [`MAX_RANGE_BITS`].
+pub fn register_range<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    bits: usize,
+) -> Result<(), SynthesisError> {
+    assert!(bits > 0 && bits <= MAX_RANGE_BITS);
+    // Expand `prod_i (x - i)` into its coefficients, lowest degree first.
+    let mut coeffs = vec![F::one()];
+    for i in 0..(1u64 << bits) {
+        let root = F::from(i);
+        let mut next = vec![F::zero(); coeffs.len() + 1];
+        for (k, c) in coeffs.iter().enumerate() {
+            next[k + 1] += c;
+            next[k] -= root * c;
+        }
+        coeffs = next;
+    }
+    let terms = coeffs
+        .into_iter()
+        .enumerate()
+        .filter(|(_, c)| !c.is_zero())
+        .map(|(k, c)| (c, if k == 0 { vec![] } else { vec![(0, k)] }))
+        .collect();
+    register(cs, &range_label(bits), 1, terms)
+}
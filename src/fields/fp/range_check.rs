@@ -0,0 +1,227 @@
+use super::{predicate, AllocatedFp, FpVar};
+use crate::{
+    cmp::CmpGadget,
+    prelude::*,
+    range_check::{RangeCheckGadget, RangeCheckStrategy},
+    Assignment,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::gr1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+impl<F: PrimeField> RangeCheckGadget<F> for FpVar<F> {
+    #[tracing::instrument(target = "gr1cs")]
+    fn enforce_bit_length_with_strategy(
+        &self,
+        n: usize,
+        strategy: RangeCheckStrategy,
+    ) -> Result<(), SynthesisError> {
+        if n >= F::MODULUS_BIT_SIZE as usize {
+            return Ok(());
+        }
+        let cs = self.cs();
+        let window_bits = match strategy {
+            RangeCheckStrategy::Windowed { window_bits }
+                if !self.is_constant()
+                    && predicate::is_registered(&cs, &predicate::range_label(window_bits)) =>
+            {
+                window_bits
+            },
+            _ => return self.to_bits_le_with_bit_length(n).map(|_| ()),
+        };
+
+        let value = self.value().ok().map(|v| v.into_bigint());
+        let mut recomposed = FpVar::zero();
+        let mut shift = F::one();
+        let mut start = 0;
+        while start < n {
+            let width = window_bits.min(n - start);
+            let label = predicate::range_label(width);
+            if predicate::is_registered(&cs, &label) {
+                let window = AllocatedFp::new_witness(ark_relations::ns!(cs, "window"), || {
+                    let value = value.get()?;
+                    let bits = (start..start + width)
+                        .map(|i| value.get_bit(i))
+                        .collect::<Vec<_>>();
+                    F::from_bigint(F::BigInt::from_bits_le(&bits)).get()
+                })?;
+                cs.enforce_constraint(&label, vec![lc!() + window.variable])?;
+                recomposed += FpVar::Var(window) * shift;
+                shift *= F::from(2u8).pow([width as u64]);
+            } else {
+                // A narrower last window without a table is checked bit by bit.
+                for i in start..start + width {
+                    let bit = Boolean::new_witness(ark_relations::ns!(cs, "bit"), || {
+                        Ok(value.get()?.get_bit(i))
+                    })?;
+                    recomposed += FpVar::from(bit) * shift;
+                    shift.double_in_place();
+                }
+            }
+            start += width;
+        }
+        recomposed.enforce_equal(self)
+    }
+
+    #[tracing::instrument(target = "gr1cs")]
+    fn to_bits_le_with_bit_length(&self, n: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let (bits, _) = self.to_bits_le_with_top_bits_zero(n)?;
+        Ok(bits)
+    }
+
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_in_range(&self, lo: F, hi: F) -> Result<Boolean<F>, SynthesisError> {
+        assert!(lo <= hi);
+        // If `self < lo`, then `self - lo` wraps around to at least `p - lo >=
+        // hi - lo`, so a single comparison suffices.
+        (self - lo).is_lt(&FpVar::Constant(hi - lo))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::AllocVar,
+        fields::fp::{predicate, FpVar},
+        range_check::{RangeCheckBatch, RangeCheckGadget, RangeCheckStrategy},
+        test_utils::constraints_added,
+        GR1CSVar,
+    };
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn check_bit_length(value: Fr, n: usize, strategy: RangeCheckStrategy, register: bool) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        if register {
+            for bits in 1..=4 {
+                predicate::register_range(&cs, bits).unwrap();
+            }
+        }
+        let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+        var.enforce_bit_length_with_strategy(n, strategy).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_enforce_bit_length() {
+        let strategies = [
+            (RangeCheckStrategy::Bits, false),
+            (RangeCheckStrategy::Windowed { window_bits: 4 }, false),
+            (RangeCheckStrategy::Windowed { window_bits: 4 }, true),
+        ];
+        for (strategy, register) in strategies {
+            for n in [1, 10, 64, Fr::MODULUS_BIT_SIZE as usize - 1] {
+                let limit = Fr::from(2u8).pow([n as u64]);
+                for value in [Fr::from(0u8), limit - Fr::from(1u8)] {
+                    assert!(check_bit_length(value, n, strategy, register));
+                }
+                for value in [limit, -Fr::from(1u8)] {
+                    assert!(!check_bit_length(value, n, strategy, register));
+                }
+            }
+            // Every element fits in `MODULUS_BIT_SIZE` bits.
+            let n = Fr::MODULUS_BIT_SIZE as usize;
+            assert!(check_bit_length(-Fr::from(1u8), n, strategy, register));
+        }
+
+        // Registered tables need fewer constraints than bits.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        predicate::register_range(&cs, 4).unwrap();
+        let var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(12345u64))).unwrap();
+        var.enforce_bit_length_with_strategy(64, RangeCheckStrategy::Windowed { window_bits: 4 })
+            .unwrap();
+        assert_eq!(cs.num_constraints(), 64 / 4 + 1);
+        assert!(cs.is_satisfied().unwrap());
+
+        // Without a table for full windows, the check costs as much as bits,
+        // and a last window without a table is decomposed into bits.
+        for (register, n, expected_constraints) in [(false, 64, 64 + 1), (true, 10, 2 + 2 + 1)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            if register {
+                predicate::register_range(&cs, 4).unwrap();
+            }
+            let var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(123u64))).unwrap();
+            let (_, num_constraints) = constraints_added(&cs, || {
+                var.enforce_bit_length_with_strategy(
+                    n,
+                    RangeCheckStrategy::Windowed { window_bits: 4 },
+                )
+                .unwrap()
+            });
+            assert_eq!(num_constraints, expected_constraints);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_to_bits_le_with_bit_length() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0b1011u8))).unwrap();
+        let bits = var.to_bits_le_with_bit_length(6).unwrap();
+        let bits = bits.iter().map(|b| b.value().unwrap()).collect::<Vec<_>>();
+        assert_eq!(bits, [true, true, false, true, false, false]);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_is_in_range() {
+        let mut rng = ark_std::test_rng();
+        let minus_one = -Fr::from(1u8);
+        let ranges = [
+            (Fr::from(0u8), Fr::from(0u8)),
+            (Fr::from(0u8), Fr::from(256u16)),
+            (Fr::from(100u8), Fr::from(200u8)),
+            (Fr::from(100u8), minus_one),
+            (Fr::rand(&mut rng), minus_one),
+        ];
+        for (lo, hi) in ranges {
+            let values = [
+                Fr::from(0u8),
+                Fr::from(99u8),
+                Fr::from(100u8),
+                Fr::from(199u8),
+                Fr::from(200u8),
+                lo,
+                hi,
+                minus_one,
+                Fr::rand(&mut rng),
+            ];
+            for value in values {
+                let cs = ConstraintSystem::<Fr>::new_ref();
+                let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+                let result = var.is_in_range(lo, hi).unwrap();
+                assert_eq!(result.value().unwrap(), lo <= value && value < hi);
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_check_batch() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(255u8))).unwrap();
+        let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(256u16))).unwrap();
+
+        RangeCheckBatch::scope(RangeCheckStrategy::Bits, |batch| {
+            batch.enforce_bit_length(&a, 16)?;
+            batch.enforce_bit_length(&a, 8)?;
+            batch.enforce_bit_length(&b, 16)?;
+            batch.enforce_bit_length(&FpVar::Constant(Fr::from(3u8)), 2)?;
+            assert_eq!(batch.len(), 2);
+            assert_eq!(cs.num_constraints(), 0);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(cs.num_constraints(), (8 + 1) + (16 + 1));
+        assert!(cs.is_satisfied().unwrap());
+
+        // The tightest bound is the one that gets enforced.
+        RangeCheckBatch::scope(RangeCheckStrategy::Bits, |batch| {
+            batch.enforce_bit_length(&b, 16)?;
+            batch.enforce_bit_length(&b, 8)
+        })
+        .unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{fields::fp::FpVar, prelude::*, Assignment};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that `self` is equal to one of the elements of `set`.
+    ///
+    /// This enforces that the product of `self - s` over all `s` in `set`
+    /// vanishes, which costs `set.len() - 1` constraints. An empty `set` is
+    /// never satisfied.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_in_set(&self, set: &[F]) -> Result<(), SynthesisError> {
+        if let FpVar::Constant(c) = self {
+            return if set.contains(c) {
+                Ok(())
+            } else {
+                Err(SynthesisError::Unsatisfiable)
+            };
+        }
+        match set.split_last() {
+            Some((last, rest)) => self
+                .vanishing_product(rest)
+                .mul_equals(&(self - *last), &Self::zero()),
+            None => Err(SynthesisError::Unsatisfiable),
+        }
+    }
+
+    /// Outputs a `Boolean` representing whether `self` is equal to one of the
+    /// elements of `set`.
+    ///
+    /// This costs `set.len() + 1` constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_in_set(&self, set: &[F]) -> Result<Boolean<F>, SynthesisError> {
+        self.vanishing_product(set).is_zero()
+    }
+
+    /// Enforces that `self` is equal to one of the elements of `set`, and
+    /// outputs the one-hot encoding of its position, i.e. a `Boolean` for
+    /// each element of `set` that is set only for the element equal to
+    /// `self`.
+    ///
+    /// The result can be used to select among values associated with the
+    /// elements of `set`. This costs `set.len() + 2` constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `set` contains duplicates, as the position would then be
+    /// ambiguous.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn index_in_set(&self, set: &[F]) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let mut sorted = set.to_vec();
+        sorted.sort_unstable();
+        assert!(sorted.windows(2).all(|w| w[0] != w[1]));
+
+        if let FpVar::Constant(c) = self {
+            if !set.contains(c) {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            return Ok(set.iter().map(|s| Boolean::constant(s == c)).collect());
+        }
+
+        let cs = self.cs();
+        let value = self.value().ok();
+        let index = set
+            .iter()
+            .map(|s| {
+                Boolean::new_witness(ark_relations::ns!(cs, "index"), || {
+                    value.map(|v| v == *s).get()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Exactly one bit is set, and it selects `self`. As the elements of
+        // `set` are distinct, this determines the bits.
+        let count = index
+            .iter()
+            .fold(Self::zero(), |acc, b| acc + Self::from(b.clone()));
+        count.enforce_equal(&Self::one())?;
+        let selected = index
+            .iter()
+            .zip(set)
+            .fold(Self::zero(), |acc, (b, s)| acc + Self::from(b.clone()) * *s);
+        selected.enforce_equal(self)?;
+        Ok(index)
+    }
+
+    /// Computes the product of `self - s` over all `s` in `set`.
+    fn vanishing_product(&self, set: &[F]) -> Self {
+        set.iter()
+            .fold(Self::one(), |product, s| product * (self - *s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar, GR1CSVar};
+    use ark_ff::UniformRand;
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_in_set() {
+        let mut rng = ark_std::test_rng();
+        let set = [
+            Fr::from(0u8),
+            Fr::from(3u8),
+            -Fr::from(1u8),
+            Fr::rand(&mut rng),
+        ];
+        let values = set
+            .iter()
+            .copied()
+            .chain([Fr::from(1u8), Fr::rand(&mut rng)]);
+        for value in values {
+            let expected = set.contains(&value);
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            assert_eq!(var.is_in_set(&set).unwrap().value().unwrap(), expected);
+            assert_eq!(
+                FpVar::Constant(value)
+                    .is_in_set(&set)
+                    .unwrap()
+                    .value()
+                    .unwrap(),
+                expected
+            );
+            assert!(cs.is_satisfied().unwrap());
+
+            let constant_index = FpVar::Constant(value).index_in_set(&set);
+            assert_eq!(
+                FpVar::Constant(value).enforce_in_set(&set).is_ok(),
+                expected
+            );
+            assert_eq!(constant_index.is_ok(), expected);
+
+            let index = var.index_in_set(&set).unwrap();
+            let index = index.iter().map(|b| b.value().unwrap());
+            assert!(index.eq(set.iter().map(|s| *s == value)));
+            var.enforce_in_set(&set).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        // Membership in a set of `n` elements costs `n - 1` constraints.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let var = FpVar::new_witness(cs.clone(), || Ok(set[1])).unwrap();
+        var.enforce_in_set(&set).unwrap();
+        assert_eq!(cs.num_constraints(), set.len() - 1);
+        assert!(var.enforce_in_set(&[]).is_err());
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
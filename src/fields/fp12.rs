@@ -1,8 +1,13 @@
-use crate::fields::{fp2::Fp2Var, fp6_3over2::Fp6Var, quadratic_extension::*, FieldVar};
+use crate::fields::{
+    fp2::Fp2Var,
+    fp6_3over2::{EmulatedFp6Var, Fp6Var},
+    quadratic_extension::*,
+    FieldVar,
+};
 use ark_ff::{
     fields::{fp12_2over3over2::*, Field},
     fp6_3over2::Fp6Config,
-    QuadExtConfig,
+    PrimeField, QuadExtConfig,
 };
 use ark_relations::gr1cs::SynthesisError;
 
@@ -11,6 +16,14 @@ use ark_relations::gr1cs::SynthesisError;
 /// This is the R1CS equivalent of `ark_ff::fp12_2over3over2::Fp12<P>`.
 pub type Fp12Var<P> = QuadExtVar<Fp6Var<<P as Fp12Config>::Fp6Config>, Fp12ConfigWrapper<P>>;
 
+/// A degree-12 extension field constructed as the tower of a
+/// quadratic extension over a cubic extension over an emulated quadratic
+/// extension field, with constraints over `BaseF`.
+/// This is the R1CS equivalent of `ark_ff::fp12_2over3over2::Fp12<P>` when the
+/// base prime field of `P` is not `BaseF`.
+pub type EmulatedFp12Var<P, BaseF> =
+    QuadExtVar<EmulatedFp6Var<<P as Fp12Config>::Fp6Config, BaseF>, Fp12ConfigWrapper<P>, BaseF>;
+
 type Fp2Config<P> = <<P as Fp12Config>::Fp6Config as Fp6Config>::Fp2Config;
 
 impl<P: Fp12Config> QuadExtVarConfig<Fp6Var<P::Fp6Config>> for Fp12ConfigWrapper<P> {
@@ -21,6 +34,19 @@ impl<P: Fp12Config> QuadExtVarConfig<Fp6Var<P::Fp6Config>> for Fp12ConfigWrapper
     }
 }
 
+impl<P: Fp12Config, BaseF: PrimeField> QuadExtVarConfig<EmulatedFp6Var<P::Fp6Config, BaseF>, BaseF>
+    for Fp12ConfigWrapper<P>
+{
+    fn mul_base_field_var_by_frob_coeff(
+        fe: &mut EmulatedFp6Var<P::Fp6Config, BaseF>,
+        power: usize,
+    ) {
+        fe.c0 *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+        fe.c1 *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+        fe.c2 *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+    }
+}
+
 impl<P: Fp12Config> Fp12Var<P> {
     /// Multiplies by a sparse element of the form `(c0 = (c0, c1, 0), c1 = (0,
     /// d1, 0))`.
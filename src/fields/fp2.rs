@@ -1,12 +1,29 @@
-use crate::fields::{fp::FpVar, quadratic_extension::*};
-use ark_ff::fields::{Fp2Config, Fp2ConfigWrapper, QuadExtConfig};
+use crate::fields::{emulated_fp::EmulatedFpVar, fp::FpVar, quadratic_extension::*};
+use ark_ff::{
+    fields::{Fp2Config, Fp2ConfigWrapper, QuadExtConfig},
+    PrimeField,
+};
 
 /// A quadratic extension field constructed over a prime field.
 /// This is the R1CS equivalent of `ark_ff::Fp2<P>`.
 pub type Fp2Var<P> = QuadExtVar<FpVar<<P as Fp2Config>::Fp>, Fp2ConfigWrapper<P>>;
 
+/// A quadratic extension field constructed over an emulated prime field,
+/// with constraints over `BaseF`.
+/// This is the R1CS equivalent of `ark_ff::Fp2<P>` when `P::Fp != BaseF`.
+pub type EmulatedFp2Var<P, BaseF> =
+    QuadExtVar<EmulatedFpVar<<P as Fp2Config>::Fp, BaseF>, Fp2ConfigWrapper<P>, BaseF>;
+
 impl<P: Fp2Config> QuadExtVarConfig<FpVar<P::Fp>> for Fp2ConfigWrapper<P> {
     fn mul_base_field_var_by_frob_coeff(fe: &mut FpVar<P::Fp>, power: usize) {
         *fe *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
     }
 }
+
+impl<P: Fp2Config, BaseF: PrimeField> QuadExtVarConfig<EmulatedFpVar<P::Fp, BaseF>, BaseF>
+    for Fp2ConfigWrapper<P>
+{
+    fn mul_base_field_var_by_frob_coeff(fe: &mut EmulatedFpVar<P::Fp, BaseF>, power: usize) {
+        *fe *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+    }
+}
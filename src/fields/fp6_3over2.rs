@@ -1,7 +1,7 @@
 use crate::fields::{cubic_extension::*, fp2::*};
 use ark_ff::{
     fields::{fp6_3over2::*, Fp2},
-    CubicExtConfig,
+    CubicExtConfig, PrimeField,
 };
 use ark_relations::gr1cs::SynthesisError;
 use ark_std::ops::MulAssign;
@@ -11,6 +11,14 @@ use ark_std::ops::MulAssign;
 /// This is the R1CS equivalent of `ark_ff::fp6_3over3::Fp6<P>`.
 pub type Fp6Var<P> = CubicExtVar<Fp2Var<<P as Fp6Config>::Fp2Config>, Fp6ConfigWrapper<P>>;
 
+/// A sextic extension field constructed as the tower of a
+/// cubic extension over an emulated quadratic extension field, with
+/// constraints over `BaseF`.
+/// This is the R1CS equivalent of `ark_ff::fp6_3over2::Fp6<P>` when the base
+/// prime field of `P` is not `BaseF`.
+pub type EmulatedFp6Var<P, BaseF> =
+    CubicExtVar<EmulatedFp2Var<<P as Fp6Config>::Fp2Config, BaseF>, Fp6ConfigWrapper<P>, BaseF>;
+
 impl<P: Fp6Config> CubicExtVarConfig<Fp2Var<P::Fp2Config>> for Fp6ConfigWrapper<P> {
     fn mul_base_field_vars_by_frob_coeff(
         c1: &mut Fp2Var<P::Fp2Config>,
@@ -22,6 +30,19 @@ impl<P: Fp6Config> CubicExtVarConfig<Fp2Var<P::Fp2Config>> for Fp6ConfigWrapper<
     }
 }
 
+impl<P: Fp6Config, BaseF: PrimeField> CubicExtVarConfig<EmulatedFp2Var<P::Fp2Config, BaseF>, BaseF>
+    for Fp6ConfigWrapper<P>
+{
+    fn mul_base_field_vars_by_frob_coeff(
+        c1: &mut EmulatedFp2Var<P::Fp2Config, BaseF>,
+        c2: &mut EmulatedFp2Var<P::Fp2Config, BaseF>,
+        power: usize,
+    ) {
+        *c1 *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+        *c2 *= Self::FROBENIUS_COEFF_C2[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+    }
+}
+
 impl<P: Fp6Config> Fp6Var<P> {
     /// Multiplies `self` by a sparse element which has `c0 == c2 == zero`.
     pub fn mul_by_0_c1_0(&self, c1: &Fp2Var<P::Fp2Config>) -> Result<Self, SynthesisError> {
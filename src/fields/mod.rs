@@ -1,5 +1,6 @@
 use ark_ff::{prelude::*, BitIteratorBE};
 use ark_relations::gr1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::vec::Vec;
 use core::{
     fmt::Debug,
     ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
@@ -7,7 +8,9 @@ use core::{
 
 use crate::{
     convert::{ToBitsGadget, ToBytesGadget, ToConstraintFieldGadget},
+    fields::fp::FpVar,
     prelude::*,
+    Assignment,
 };
 
 /// This module contains a generic implementation of cubic extension field
@@ -233,4 +236,443 @@ pub trait FieldVar<F: Field, ConstraintF: PrimeField>:
         }
         Ok(res)
     }
+
+    /// Computes `self^bits`, where `bits` is a *little-endian* bit-wise
+    /// decomposition of the exponent, using fixed windows of `window_bits`
+    /// bits.
+    ///
+    /// The powers `self^0, ..., self^(2^window_bits - 1)` are precomputed
+    /// once, and each window then costs `window_bits` squarings, a selection
+    /// from the table, and a single multiplication, compared to one
+    /// multiplication and one selection per bit in [`Self::pow_le`]. A
+    /// selection from the table costs `2^window_bits - 1` conditional
+    /// selections, so wide windows only pay off when multiplication is much
+    /// more expensive than selection, as in extension and emulated fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_bits` is zero.
+    #[tracing::instrument(target = "gr1cs", skip(self, bits))]
+    fn pow_le_windowed(
+        &self,
+        bits: &[Boolean<ConstraintF>],
+        window_bits: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert!(window_bits > 0);
+        let window_bits = window_bits.min(bits.len().max(1));
+        let mut table = Vec::with_capacity(1 << window_bits);
+        table.push(Self::one());
+        table.push(self.clone());
+        for i in 2..(1 << window_bits) {
+            let power = table[i - 1].clone() * self;
+            table.push(power);
+        }
+
+        // Windows are processed from the most significant one, which may be
+        // shorter than the others.
+        let mut res: Option<Self> = None;
+        for window in bits.chunks(window_bits).rev() {
+            let position = window.iter().rev().cloned().collect::<Vec<_>>();
+            let power = Self::conditionally_select_power_of_two_vector(
+                &position,
+                &table[..1 << window.len()],
+            )?;
+            res = Some(match res {
+                None => power,
+                Some(mut res) => {
+                    for _ in 0..window.len() {
+                        res.square_in_place()?;
+                    }
+                    res * power
+                },
+            });
+        }
+        Ok(res.unwrap_or_else(Self::one))
+    }
+
+    /// Computes `self^exp`, where `exp` is interpreted as an integer smaller
+    /// than `2^bit_bound`.
+    ///
+    /// `exp` is decomposed into `bit_bound` bits, which enforces the bound, and
+    /// the power is computed with [`Self::pow_le_windowed`] using windows of
+    /// `window_bits` bits. If `bit_bound >= ConstraintF::MODULUS_BIT_SIZE`,
+    /// `exp` may be any element of `ConstraintF`, and is decomposed into its
+    /// canonical bits instead.
+    ///
+    /// With `window_bits = 1`, this costs one multiplication and one selection
+    /// per bit, like [`Self::pow_le`]. Windows of 2 bits halve the number of
+    /// multiplications, but need 3 selections per 2 bits instead of 2, so they
+    /// only pay off when a multiplication costs more than a selection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_bits` is zero.
+    #[tracing::instrument(target = "gr1cs", skip(self, exp))]
+    fn pow_with_bit_bound(
+        &self,
+        exp: &FpVar<ConstraintF>,
+        bit_bound: usize,
+        window_bits: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert!(window_bits > 0);
+        if let FpVar::Constant(exp) = exp {
+            let exp = exp.into_bigint();
+            if exp.num_bits() as usize > bit_bound {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            return self.pow_by_constant(exp);
+        }
+        let bits = if bit_bound < ConstraintF::MODULUS_BIT_SIZE as usize {
+            exp.to_bits_le_with_bit_length(bit_bound)?
+        } else {
+            exp.to_bits_le()?
+        };
+        self.pow_le_windowed(&bits, window_bits)
+    }
+
+    /// Computes a square root of `self`.
+    ///
+    /// The root is supplied as a witness hint and checked with a single
+    /// squaring constraint, so either root may be returned. The constraint
+    /// system will be unsatisfiable when `self` is not a square.
+    fn sqrt(&self) -> Result<Self, SynthesisError> {
+        if self.is_constant() {
+            let root = self.value()?.sqrt().ok_or(SynthesisError::Unsatisfiable)?;
+            return Ok(Self::constant(root));
+        }
+        let cs = self.cs();
+        let root = Self::new_witness(ark_relations::ns!(cs, "sqrt"), || {
+            Ok(self.value()?.sqrt().unwrap_or(F::zero()))
+        })?;
+        root.square_equals(self)?;
+        Ok(root)
+    }
+
+    /// Returns a `Boolean` representing whether `self` is a square in `F`.
+    /// Zero is considered a square.
+    ///
+    /// The prover supplies the answer together with a square root of either
+    /// `self` (if it is a square) or `n * self` for a fixed quadratic
+    /// non-residue `n` (if it is not), which is checked with a single
+    /// squaring constraint.
+    fn is_square(&self) -> Result<Boolean<ConstraintF>, SynthesisError> {
+        if self.is_constant() {
+            return Ok(Boolean::constant(!self.value()?.legendre().is_qnr()));
+        }
+        let cs = self.cs();
+        let non_residue = quadratic_non_residue::<F>();
+        let claim = Boolean::new_witness(ark_relations::ns!(cs, "is_square"), || {
+            Ok(!self.value()?.legendre().is_qnr())
+        })?;
+        let root = Self::new_witness(ark_relations::ns!(cs, "root"), || {
+            let value = self.value()?;
+            let square = if value.legendre().is_qnr() {
+                value * non_residue
+            } else {
+                value
+            };
+            Ok(square.sqrt().unwrap_or(F::zero()))
+        })?;
+        let square = claim.select(self, &(self.clone() * non_residue))?;
+        root.square_equals(&square)?;
+        // For non-zero `self`, exactly one of `self` and `n * self` is a square,
+        // so `claim` is fully determined. For zero both are, so we additionally
+        // accept zero regardless of `claim`.
+        Ok(claim | self.is_zero()?)
+    }
+
+    /// Computes the Legendre symbol of `self`, i.e. `0` if `self` is zero, `1`
+    /// if `self` is a non-zero square, and `-1` otherwise.
+    fn legendre(&self) -> Result<FpVar<ConstraintF>, SynthesisError> {
+        let is_square = FpVar::from(self.is_square()?);
+        let is_zero = FpVar::from(self.is_zero()?);
+        // `is_zero` implies `is_square`, so the symbol is `2 * is_square - 1 -
+        // is_zero`.
+        Ok(is_square.double()? - ConstraintF::one() - is_zero)
+    }
+
+    /// Computes the inverses of all elements of `elems`. Zero entries are
+    /// mapped to zero, matching `ark_ff::batch_inversion`.
+    ///
+    /// The witnesses for all inverses are computed with a single native
+    /// inversion. Every output `y` of a non-constant input `x` is checked
+    /// by enforcing `x * (1 - x * y) == 0` and `y * (1 - x * y) == 0`, which
+    /// forces `y = x^{-1}` if `x != 0` and `y = 0` otherwise.
+    ///
+    /// This costs three multiplications per non-constant element (three
+    /// constraints for `FpVar`), i.e. more than calling [`Self::inverse`] on
+    /// each element, which costs one but rejects zero. The saving is in
+    /// witness generation only. If all elements are known to be non-zero,
+    /// [`Self::batch_inverse_unchecked`] is cheaper.
+    fn batch_inverse(elems: &[Self]) -> Result<Vec<Self>, SynthesisError> {
+        let inverses = allocate_batch_inverses(elems)?;
+        for (x, y) in elems.iter().zip(&inverses) {
+            if !x.is_constant() {
+                let one_minus_xy = Self::one() - &(x.clone() * y);
+                x.mul_equals(&one_minus_xy, &Self::zero())?;
+                y.mul_equals(&one_minus_xy, &Self::zero())?;
+            }
+        }
+        Ok(inverses)
+    }
+
+    /// Computes the inverses of all elements of `elems`.
+    ///
+    /// The witnesses for all inverses are computed with a single native
+    /// inversion, and each output `y` of a non-constant input `x` is checked
+    /// by enforcing `x * y == 1`. This costs one multiplication per
+    /// non-constant element, the same as [`Self::inverse`], so the saving
+    /// over inverting the elements one by one is in witness generation only.
+    ///
+    /// The precondition for this method is that no element of `elems` is zero.
+    /// If an element is zero, the constraint system will be unsatisfiable;
+    /// use [`Self::batch_inverse`] if zero entries should be mapped to zero
+    /// instead.
+    fn batch_inverse_unchecked(elems: &[Self]) -> Result<Vec<Self>, SynthesisError> {
+        let inverses = allocate_batch_inverses(elems)?;
+        for (x, y) in elems.iter().zip(&inverses) {
+            if !x.is_constant() {
+                x.mul_equals(y, &Self::one())?;
+            }
+        }
+        Ok(inverses)
+    }
+}
+
+/// Allocates the inverses of `elems` (with zero mapped to zero) without
+/// enforcing any constraints. Constant inputs yield constant outputs.
+fn allocate_batch_inverses<F, ConstraintF, V>(elems: &[V]) -> Result<Vec<V>, SynthesisError>
+where
+    F: Field,
+    ConstraintF: PrimeField,
+    V: FieldVar<F, ConstraintF>,
+{
+    let cs = elems.cs();
+    // `batch_inversion` leaves zero entries untouched.
+    let inverses = elems
+        .iter()
+        .map(|e| e.value())
+        .collect::<Result<Vec<_>, _>>()
+        .map(|mut values| {
+            ark_ff::batch_inversion(&mut values);
+            values
+        })
+        .ok();
+    elems
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            if e.is_constant() {
+                Ok(V::constant(e.value()?.inverse().unwrap_or(F::zero())))
+            } else {
+                V::new_witness(ark_relations::ns!(cs, "inverse"), || {
+                    inverses.as_ref().map(|v| v[i]).get()
+                })
+            }
+        })
+        .collect()
+}
+
+/// Returns a fixed quadratic non-residue of `F`.
+fn quadratic_non_residue<F: Field>() -> F {
+    let degree = F::extension_degree();
+    (1u64..)
+        .map(|i| {
+            F::from_base_prime_field_elems((0..degree).map(|j| F::BasePrimeField::from(i + j)))
+                .unwrap()
+        })
+        .find(|c| c.legendre().is_qnr())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::FieldVar;
+    use crate::{
+        alloc::AllocVar,
+        convert::ToBitsGadget,
+        fields::{fp::FpVar, fp2::Fp2Var, fp3::Fp3Var},
+        range_check::RangeCheckGadget,
+        test_utils::constraints_added,
+        GR1CSVar,
+    };
+    use ark_ff::{Field, PrimeField, UniformRand};
+    use ark_mnt6_298::{Fq as MntFq, Fq3, Fq3Config};
+    use ark_relations::gr1cs::ConstraintSystem;
+
+    fn sqrt_test<F: Field, ConstraintF: PrimeField, V: FieldVar<F, ConstraintF>>() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        for a in (0..10).map(|_| F::rand(&mut rng)).chain([F::zero()]) {
+            let a_var = V::new_witness(cs.clone(), || Ok(a)).unwrap();
+
+            let is_square = !a.legendre().is_qnr();
+            assert_eq!(a_var.is_square().unwrap().value().unwrap(), is_square);
+
+            let legendre = if a.is_zero() {
+                ConstraintF::zero()
+            } else if is_square {
+                ConstraintF::one()
+            } else {
+                -ConstraintF::one()
+            };
+            assert_eq!(a_var.legendre().unwrap().value().unwrap(), legendre);
+
+            if is_square {
+                assert_eq!(a_var.sqrt().unwrap().value().unwrap().square(), a);
+            }
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // Taking the square root of a non-residue is unsatisfiable.
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let a = core::iter::repeat_with(|| F::rand(&mut rng))
+            .find(|a| a.legendre().is_qnr())
+            .unwrap();
+        let a_var = V::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let _ = a_var.sqrt().unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    fn batch_inverse_test<F: Field, ConstraintF: PrimeField, V: FieldVar<F, ConstraintF>>() {
+        let mut rng = ark_std::test_rng();
+        let values = (0..10)
+            .map(|_| F::rand(&mut rng))
+            .chain([F::zero(), F::one()])
+            .collect::<Vec<_>>();
+        let expected = values
+            .iter()
+            .map(|v| v.inverse().unwrap_or(F::zero()))
+            .collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let mut vars = values
+            .iter()
+            .map(|v| V::new_witness(cs.clone(), || Ok(*v)).unwrap())
+            .collect::<Vec<_>>();
+        vars.push(V::constant(values[0]));
+
+        // Every non-constant element costs one multiplication and two checks
+        // of the cost of an `inverse`, while constants are free.
+        let (_, mul_cost) = constraints_added(&cs, || vars[0].clone() * &vars[1]);
+        let (_, inverse_cost) = constraints_added(&cs, || vars[0].inverse().unwrap());
+        let (inverses, batch_cost) = constraints_added(&cs, || V::batch_inverse(&vars).unwrap());
+        assert_eq!(batch_cost, values.len() * (mul_cost + 2 * inverse_cost));
+        assert_eq!(inverses.len(), vars.len());
+        for (inverse, expected) in inverses.iter().zip(expected.iter().chain([&expected[0]])) {
+            assert_eq!(inverse.value().unwrap(), *expected);
+        }
+        assert!(inverses.last().unwrap().is_constant());
+        assert!(cs.is_satisfied().unwrap());
+
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let vars = values[..10]
+            .iter()
+            .map(|v| V::new_witness(cs.clone(), || Ok(*v)).unwrap())
+            .collect::<Vec<_>>();
+        let (inverses, unchecked_cost) =
+            constraints_added(&cs, || V::batch_inverse_unchecked(&vars).unwrap());
+        assert_eq!(unchecked_cost, vars.len() * inverse_cost);
+        for (inverse, expected) in inverses.iter().zip(&expected) {
+            assert_eq!(inverse.value().unwrap(), *expected);
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // Zero entries make the unchecked variant unsatisfiable.
+        let zero = V::new_witness(cs.clone(), || Ok(F::zero())).unwrap();
+        let _ = V::batch_inverse_unchecked(&[zero]).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    fn pow_test<F: Field, ConstraintF: PrimeField, V: FieldVar<F, ConstraintF>>() {
+        let mut rng = ark_std::test_rng();
+        let base = F::rand(&mut rng);
+        let minus_one = -ConstraintF::one();
+        let exps = [
+            (ConstraintF::zero(), 8),
+            (ConstraintF::one(), 1),
+            (ConstraintF::from(255u8), 8),
+            (ConstraintF::from(1000u16), 10),
+            (ConstraintF::from(u64::MAX), 64),
+            (minus_one, ConstraintF::MODULUS_BIT_SIZE as usize),
+        ];
+        for (exp, bit_bound) in exps {
+            let expected = base.pow(exp.into_bigint());
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let base_var = V::new_witness(cs.clone(), || Ok(base)).unwrap();
+            let exp_var = FpVar::new_witness(cs.clone(), || Ok(exp)).unwrap();
+            for window_bits in [1, 2] {
+                let result = base_var
+                    .pow_with_bit_bound(&exp_var, bit_bound, window_bits)
+                    .unwrap();
+                assert_eq!(result.value().unwrap(), expected);
+                let result = base_var
+                    .pow_with_bit_bound(&FpVar::Constant(exp), bit_bound, window_bits)
+                    .unwrap();
+                assert_eq!(result.value().unwrap(), expected);
+            }
+            assert!(cs.is_satisfied().unwrap());
+
+            let bits = exp_var.to_bits_le().unwrap();
+            for window_bits in [1, 3, 5] {
+                let result = base_var.pow_le_windowed(&bits, window_bits).unwrap();
+                assert_eq!(result.value().unwrap(), expected);
+            }
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // An exponent exceeding the bound is unsatisfiable.
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let base_var = V::new_witness(cs.clone(), || Ok(base)).unwrap();
+        let exp_var = FpVar::new_witness(cs.clone(), || Ok(ConstraintF::from(256u16))).unwrap();
+        let _ = base_var.pow_with_bit_bound(&exp_var, 8, 2).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    /// Returns the number of constraints that `pow_with_bit_bound` needs for a
+    /// 64-bit exponent with windows of `window_bits` bits.
+    fn pow_cost<F: Field, ConstraintF: PrimeField, V: FieldVar<F, ConstraintF>>(
+        window_bits: usize,
+    ) -> usize {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let base_var = V::new_witness(cs.clone(), || Ok(F::rand(&mut rng))).unwrap();
+        let exp_var = FpVar::new_witness(cs.clone(), || Ok(ConstraintF::from(u64::MAX))).unwrap();
+        let bits = exp_var.to_bits_le_with_bit_length(64).unwrap();
+        constraints_added(&cs, || {
+            base_var.pow_le_windowed(&bits, window_bits).unwrap()
+        })
+        .1
+    }
+
+    #[test]
+    fn test_pow_with_bit_bound() {
+        type Fq = ark_bls12_381::Fq;
+        pow_test::<Fq, Fq, FpVar<Fq>>();
+        pow_test::<ark_bls12_381::Fq2, Fq, Fp2Var<ark_bls12_381::Fq2Config>>();
+        pow_test::<Fq3, MntFq, Fp3Var<Fq3Config>>();
+
+        // Multiplications in extension fields cost more than selections, so
+        // 2-bit windows are cheaper, while wider ones are not.
+        type Fq2Var = Fp2Var<ark_bls12_381::Fq2Config>;
+        let cost = |window_bits| pow_cost::<ark_bls12_381::Fq2, Fq, Fq2Var>(window_bits);
+        assert!(cost(2) < cost(1));
+        assert!(cost(3) > cost(2));
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        type Fq = ark_bls12_381::Fq;
+        batch_inverse_test::<Fq, Fq, FpVar<Fq>>();
+        batch_inverse_test::<ark_bls12_381::Fq2, Fq, Fp2Var<ark_bls12_381::Fq2Config>>();
+        batch_inverse_test::<Fq3, MntFq, Fp3Var<Fq3Config>>();
+    }
+
+    #[test]
+    fn test_sqrt() {
+        type Fq = ark_bls12_381::Fq;
+        sqrt_test::<Fq, Fq, FpVar<Fq>>();
+        sqrt_test::<ark_bls12_381::Fq2, Fq, Fp2Var<ark_bls12_381::Fq2Config>>();
+        sqrt_test::<Fq3, MntFq, Fp3Var<Fq3Config>>();
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
 };
 use ark_ff::{
     fields::{Field, QuadExtConfig, QuadExtField},
-    Zero,
+    PrimeField, Zero,
 };
 use ark_relations::gr1cs::{ConstraintSystemRef, Namespace, SynthesisError};
 use core::{borrow::Borrow, marker::PhantomData};
@@ -14,11 +14,19 @@ use educe::Educe;
 
 /// This struct is the `R1CS` equivalent of the quadratic extension field type
 /// in `ark-ff`, i.e. `ark_ff::QuadExtField`.
+///
+/// Constraints are generated over `ConstraintF`, which defaults to the base
+/// prime field of `P`. Setting it to a different field, with `BF` built from
+/// `EmulatedFpVar`, gives an emulated extension field such as
+/// [`EmulatedFp2Var`](crate::fields::fp2::EmulatedFp2Var).
 #[derive(Educe)]
 #[educe(Debug, Clone)]
 #[must_use]
-pub struct QuadExtVar<BF: FieldVar<P::BaseField, P::BasePrimeField>, P: QuadExtVarConfig<BF>>
-where
+pub struct QuadExtVar<
+    BF: FieldVar<P::BaseField, ConstraintF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField = <P as QuadExtConfig>::BasePrimeField,
+> where
     for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
 {
     /// The zero-th coefficient of this field element.
@@ -26,14 +34,15 @@ where
     /// The first coefficient of this field element.
     pub c1: BF,
     #[educe(Debug(ignore))]
-    _params: PhantomData<P>,
+    _params: PhantomData<(P, ConstraintF)>,
 }
 
 /// This trait describes parameters that are used to implement arithmetic for
 /// `QuadExtVar`.
-pub trait QuadExtVarConfig<BF: FieldVar<Self::BaseField, Self::BasePrimeField>>:
-    QuadExtConfig
-where
+pub trait QuadExtVarConfig<
+    BF: FieldVar<Self::BaseField, ConstraintF>,
+    ConstraintF: PrimeField = <Self as QuadExtConfig>::BasePrimeField,
+>: QuadExtConfig where
     for<'a> &'a BF: FieldOpsBounds<'a, Self::BaseField, BF>,
 {
     /// Multiply the base field of the `QuadExtVar` by the appropriate Frobenius
@@ -42,8 +51,11 @@ where
     fn mul_base_field_var_by_frob_coeff(fe: &mut BF, power: usize);
 }
 
-impl<BF: FieldVar<P::BaseField, P::BasePrimeField>, P: QuadExtVarConfig<BF>> QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> QuadExtVar<BF, P, ConstraintF>
 where
+    BF: FieldVar<P::BaseField, ConstraintF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
     for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
 {
     /// Constructs a `QuadExtVar` from the underlying coefficients.
@@ -89,7 +101,7 @@ where
     #[tracing::instrument(target = "gr1cs", skip(exponent))]
     pub fn cyclotomic_exp(&self, exponent: impl AsRef<[u64]>) -> Result<Self, SynthesisError>
     where
-        Self: FieldVar<QuadExtField<P>, P::BasePrimeField>,
+        Self: FieldVar<QuadExtField<P>, ConstraintF>,
     {
         let mut res = Self::one();
         let self_inverse = self.unitary_inverse()?;
@@ -117,15 +129,16 @@ where
     }
 }
 
-impl<BF, P> GR1CSVar<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> GR1CSVar<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     type Value = QuadExtField<P>;
 
-    fn cs(&self) -> ConstraintSystemRef<P::BasePrimeField> {
+    fn cs(&self) -> ConstraintSystemRef<ConstraintF> {
         [&self.c0, &self.c1].cs()
     }
 
@@ -138,39 +151,45 @@ where
     }
 }
 
-impl<BF, P> From<Boolean<P::BasePrimeField>> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> From<Boolean<ConstraintF>> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
-    fn from(other: Boolean<P::BasePrimeField>) -> Self {
+    fn from(other: Boolean<ConstraintF>) -> Self {
         let c0 = BF::from(other);
         let c1 = BF::zero();
         Self::new(c0, c1)
     }
 }
 
-impl<'a, BF, P> FieldOpsBounds<'a, QuadExtField<P>, QuadExtVar<BF, P>> for QuadExtVar<BF, P>
+impl<'a, BF, P, ConstraintF> FieldOpsBounds<'a, QuadExtField<P>, QuadExtVar<BF, P, ConstraintF>>
+    for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
 }
-impl<'a, BF, P> FieldOpsBounds<'a, QuadExtField<P>, QuadExtVar<BF, P>> for &'a QuadExtVar<BF, P>
+impl<'a, BF, P, ConstraintF> FieldOpsBounds<'a, QuadExtField<P>, QuadExtVar<BF, P, ConstraintF>>
+    for &'a QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
 }
 
-impl<BF, P> FieldVar<QuadExtField<P>, P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> FieldVar<QuadExtField<P>, ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     fn constant(other: QuadExtField<P>) -> Self {
         let c0 = BF::constant(other.c0);
@@ -295,49 +314,49 @@ where
 }
 
 impl_bounded_ops!(
-    QuadExtVar<BF, P>,
+    QuadExtVar<BF, P, ConstraintF>,
     QuadExtField<P>,
     Add,
     add,
     AddAssign,
     add_assign,
-    |this: &'a QuadExtVar<BF, P>, other: &'a QuadExtVar<BF, P>| {
+    |this: &'a QuadExtVar<BF, P, ConstraintF>, other: &'a QuadExtVar<BF, P, ConstraintF>| {
         let c0 = &this.c0 + &other.c0;
         let c1 = &this.c1 + &other.c1;
         QuadExtVar::new(c0, c1)
     },
-    |this: &'a QuadExtVar<BF, P>, other: QuadExtField<P>| {
+    |this: &'a QuadExtVar<BF, P, ConstraintF>, other: QuadExtField<P>| {
         this + QuadExtVar::constant(other)
     },
-    (BF: FieldVar<P::BaseField, P::BasePrimeField>, P: QuadExtVarConfig<BF>),
+    (BF: FieldVar<P::BaseField, ConstraintF>, P: QuadExtVarConfig<BF, ConstraintF>, ConstraintF: PrimeField),
     for <'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>
 );
 impl_bounded_ops!(
-    QuadExtVar<BF, P>,
+    QuadExtVar<BF, P, ConstraintF>,
     QuadExtField<P>,
     Sub,
     sub,
     SubAssign,
     sub_assign,
-    |this: &'a QuadExtVar<BF, P>, other: &'a QuadExtVar<BF, P>| {
+    |this: &'a QuadExtVar<BF, P, ConstraintF>, other: &'a QuadExtVar<BF, P, ConstraintF>| {
         let c0 = &this.c0 - &other.c0;
         let c1 = &this.c1 - &other.c1;
         QuadExtVar::new(c0, c1)
     },
-    |this: &'a QuadExtVar<BF, P>, other: QuadExtField<P>| {
+    |this: &'a QuadExtVar<BF, P, ConstraintF>, other: QuadExtField<P>| {
         this - QuadExtVar::constant(other)
     },
-    (BF: FieldVar<P::BaseField, P::BasePrimeField>, P: QuadExtVarConfig<BF>),
+    (BF: FieldVar<P::BaseField, ConstraintF>, P: QuadExtVarConfig<BF, ConstraintF>, ConstraintF: PrimeField),
     for <'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>
 );
 impl_bounded_ops!(
-    QuadExtVar<BF, P>,
+    QuadExtVar<BF, P, ConstraintF>,
     QuadExtField<P>,
     Mul,
     mul,
     MulAssign,
     mul_assign,
-    |this: &'a QuadExtVar<BF, P>, other: &'a QuadExtVar<BF, P>| {
+    |this: &'a QuadExtVar<BF, P, ConstraintF>, other: &'a QuadExtVar<BF, P, ConstraintF>| {
         // Karatsuba multiplication for Fp2:
         //     v0 = A.c0 * B.c0
         //     v1 = A.c1 * B.c1
@@ -358,24 +377,25 @@ impl_bounded_ops!(
         result.c1 *= &other.c0 + &other.c1;
         result.c1 -= &v0;
         result.c1 -= &v1;
-        result.c0 = v0 + &QuadExtVar::<BF, P>::mul_base_field_by_nonresidue(&v1).unwrap();
+        result.c0 = v0 + &QuadExtVar::<BF, P, ConstraintF>::mul_base_field_by_nonresidue(&v1).unwrap();
         result
     },
-    |this: &'a QuadExtVar<BF, P>, other: QuadExtField<P>| {
+    |this: &'a QuadExtVar<BF, P, ConstraintF>, other: QuadExtField<P>| {
         this * QuadExtVar::constant(other)
     },
-    (BF: FieldVar<P::BaseField, P::BasePrimeField>, P: QuadExtVarConfig<BF>),
+    (BF: FieldVar<P::BaseField, ConstraintF>, P: QuadExtVarConfig<BF, ConstraintF>, ConstraintF: PrimeField),
     for <'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>
 );
 
-impl<BF, P> EqGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> EqGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     #[tracing::instrument(target = "gr1cs")]
-    fn is_eq(&self, other: &Self) -> Result<Boolean<P::BasePrimeField>, SynthesisError> {
+    fn is_eq(&self, other: &Self) -> Result<Boolean<ConstraintF>, SynthesisError> {
         let b0 = self.c0.is_eq(&other.c0)?;
         let b1 = self.c1.is_eq(&other.c1)?;
         Ok(b0 & b1)
@@ -386,7 +406,7 @@ where
     fn conditional_enforce_equal(
         &self,
         other: &Self,
-        condition: &Boolean<P::BasePrimeField>,
+        condition: &Boolean<ConstraintF>,
     ) -> Result<(), SynthesisError> {
         self.c0.conditional_enforce_equal(&other.c0, condition)?;
         self.c1.conditional_enforce_equal(&other.c1, condition)?;
@@ -398,21 +418,22 @@ where
     fn conditional_enforce_not_equal(
         &self,
         other: &Self,
-        condition: &Boolean<P::BasePrimeField>,
+        condition: &Boolean<ConstraintF>,
     ) -> Result<(), SynthesisError> {
         let is_equal = self.is_eq(other)?;
         (is_equal & condition).enforce_equal(&Boolean::FALSE)
     }
 }
 
-impl<BF, P> ToBitsGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> ToBitsGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     #[tracing::instrument(target = "gr1cs")]
-    fn to_bits_le(&self) -> Result<Vec<Boolean<P::BasePrimeField>>, SynthesisError> {
+    fn to_bits_le(&self) -> Result<Vec<Boolean<ConstraintF>>, SynthesisError> {
         let mut c0 = self.c0.to_bits_le()?;
         let mut c1 = self.c1.to_bits_le()?;
         c0.append(&mut c1);
@@ -420,7 +441,7 @@ where
     }
 
     #[tracing::instrument(target = "gr1cs")]
-    fn to_non_unique_bits_le(&self) -> Result<Vec<Boolean<P::BasePrimeField>>, SynthesisError> {
+    fn to_non_unique_bits_le(&self) -> Result<Vec<Boolean<ConstraintF>>, SynthesisError> {
         let mut c0 = self.c0.to_non_unique_bits_le()?;
         let mut c1 = self.c1.to_non_unique_bits_le()?;
         c0.append(&mut c1);
@@ -428,14 +449,15 @@ where
     }
 }
 
-impl<BF, P> ToBytesGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> ToBytesGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     #[tracing::instrument(target = "gr1cs")]
-    fn to_bytes_le(&self) -> Result<Vec<UInt8<P::BasePrimeField>>, SynthesisError> {
+    fn to_bytes_le(&self) -> Result<Vec<UInt8<ConstraintF>>, SynthesisError> {
         let mut c0 = self.c0.to_bytes_le()?;
         let mut c1 = self.c1.to_bytes_le()?;
         c0.append(&mut c1);
@@ -443,7 +465,7 @@ where
     }
 
     #[tracing::instrument(target = "gr1cs")]
-    fn to_non_unique_bytes_le(&self) -> Result<Vec<UInt8<P::BasePrimeField>>, SynthesisError> {
+    fn to_non_unique_bytes_le(&self) -> Result<Vec<UInt8<ConstraintF>>, SynthesisError> {
         let mut c0 = self.c0.to_non_unique_bytes_le()?;
         let mut c1 = self.c1.to_non_unique_bytes_le()?;
         c0.append(&mut c1);
@@ -451,15 +473,16 @@ where
     }
 }
 
-impl<BF, P> ToConstraintFieldGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> ToConstraintFieldGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
-    BF: ToConstraintFieldGadget<P::BasePrimeField>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
+    BF: ToConstraintFieldGadget<ConstraintF>,
 {
     #[tracing::instrument(target = "gr1cs")]
-    fn to_constraint_field(&self) -> Result<Vec<FpVar<P::BasePrimeField>>, SynthesisError> {
+    fn to_constraint_field(&self) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
         let mut res = Vec::new();
 
         res.extend_from_slice(&self.c0.to_constraint_field()?);
@@ -469,15 +492,16 @@ where
     }
 }
 
-impl<BF, P> CondSelectGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> CondSelectGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     #[inline]
     fn conditionally_select(
-        cond: &Boolean<P::BasePrimeField>,
+        cond: &Boolean<ConstraintF>,
         true_value: &Self,
         false_value: &Self,
     ) -> Result<Self, SynthesisError> {
@@ -487,18 +511,19 @@ where
     }
 }
 
-impl<BF, P> TwoBitLookupGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> TwoBitLookupGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>
-        + TwoBitLookupGadget<P::BasePrimeField, TableConstant = P::BaseField>,
+    BF: FieldVar<P::BaseField, ConstraintF>
+        + TwoBitLookupGadget<ConstraintF, TableConstant = P::BaseField>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     type TableConstant = QuadExtField<P>;
 
     #[tracing::instrument(target = "gr1cs")]
     fn two_bit_lookup(
-        b: &[Boolean<P::BasePrimeField>],
+        b: &[Boolean<ConstraintF>],
         c: &[Self::TableConstant],
     ) -> Result<Self, SynthesisError> {
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
@@ -509,19 +534,20 @@ where
     }
 }
 
-impl<BF, P> ThreeBitCondNegLookupGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> ThreeBitCondNegLookupGadget<ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>
-        + ThreeBitCondNegLookupGadget<P::BasePrimeField, TableConstant = P::BaseField>,
+    BF: FieldVar<P::BaseField, ConstraintF>
+        + ThreeBitCondNegLookupGadget<ConstraintF, TableConstant = P::BaseField>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     type TableConstant = QuadExtField<P>;
 
     #[tracing::instrument(target = "gr1cs")]
     fn three_bit_cond_neg_lookup(
-        b: &[Boolean<P::BasePrimeField>],
-        b0b1: &Boolean<P::BasePrimeField>,
+        b: &[Boolean<ConstraintF>],
+        b0b1: &Boolean<ConstraintF>,
         c: &[Self::TableConstant],
     ) -> Result<Self, SynthesisError> {
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
@@ -532,14 +558,15 @@ where
     }
 }
 
-impl<BF, P> AllocVar<QuadExtField<P>, P::BasePrimeField> for QuadExtVar<BF, P>
+impl<BF, P, ConstraintF> AllocVar<QuadExtField<P>, ConstraintF> for QuadExtVar<BF, P, ConstraintF>
 where
-    BF: FieldVar<P::BaseField, P::BasePrimeField>,
+    BF: FieldVar<P::BaseField, ConstraintF>,
     for<'b> &'b BF: FieldOpsBounds<'b, P::BaseField, BF>,
-    P: QuadExtVarConfig<BF>,
+    P: QuadExtVarConfig<BF, ConstraintF>,
+    ConstraintF: PrimeField,
 {
     fn new_variable<T: Borrow<QuadExtField<P>>>(
-        cs: impl Into<Namespace<P::BasePrimeField>>,
+        cs: impl Into<Namespace<ConstraintF>>,
         f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: AllocationMode,
     ) -> Result<Self, SynthesisError> {
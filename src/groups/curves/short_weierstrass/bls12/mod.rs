@@ -1,9 +1,12 @@
 use ark_ec::{
     bls12::{Bls12Config, G1Prepared, G2Prepared, TwistType},
+    scalar_mul::glv::GLVConfig,
     short_weierstrass::Affine as GroupAffine,
+    CurveConfig,
 };
-use ark_ff::{BitIteratorBE, Field, One};
+use ark_ff::{fields::fp6_3over2::Fp6Config, BitIteratorBE, Field, Fp2, One, PrimeField, Zero};
 use ark_relations::gr1cs::{Namespace, SynthesisError};
+use num_bigint::BigUint;
 
 use crate::{
     fields::{fp::FpVar, fp2::Fp2Var, FieldVar},
@@ -12,6 +15,11 @@ use crate::{
 };
 
 /// Represents a projective point in G1.
+///
+/// The generic [`CurveVar::enforce_prime_order`] and
+/// [`CurveVar::is_in_prime_order_subgroup`] multiply by the group order; use
+/// [`g1_enforce_prime_order`] and [`g1_is_in_prime_order_subgroup`] for the
+/// much cheaper endomorphism-based check.
 pub type G1Var<P> = ProjectiveVar<<P as Bls12Config>::G1Config, FpVar<<P as Bls12Config>::Fp>>;
 
 /// Represents an affine point on G1. Should be used only for comparison and
@@ -20,6 +28,11 @@ pub type G1Var<P> = ProjectiveVar<<P as Bls12Config>::G1Config, FpVar<<P as Bls1
 pub type G1AffineVar<P> = AffineVar<<P as Bls12Config>::G1Config, FpVar<<P as Bls12Config>::Fp>>;
 
 /// Represents a projective point in G2.
+///
+/// The generic [`CurveVar::enforce_prime_order`] and
+/// [`CurveVar::is_in_prime_order_subgroup`] multiply by the group order; use
+/// [`g2_enforce_prime_order`] and [`g2_is_in_prime_order_subgroup`] for the
+/// much cheaper endomorphism-based check.
 pub type G2Var<P> = ProjectiveVar<<P as Bls12Config>::G2Config, Fp2G<P>>;
 /// Represents an affine point on G2. Should be used only for comparison and
 /// when a canonical representation of a point is required, and not for
@@ -100,6 +113,7 @@ impl<P: Bls12Config> ToBytesGadget<P::Fp> for G1PreparedVar<P> {
 
 type Fp2G<P> = Fp2Var<<P as Bls12Config>::Fp2Config>;
 type LCoeff<P> = (Fp2G<P>, Fp2G<P>);
+type G1ScalarField<P> = <<P as Bls12Config>::G1Config as CurveConfig>::ScalarField;
 /// Represents the cached precomputation that can be performed on a G2 element
 /// which enables speeding up pairing computation.
 #[derive(Educe)]
@@ -257,3 +271,107 @@ impl<P: Bls12Config> G2PreparedVar<P> {
         }
     }
 }
+
+/// The primitive cube root of unity `β` in `P::Fp` for which the endomorphism
+/// `φ(x, y) = (β * x, y)` acts on G1 as multiplication by `-x²`, where `x` is
+/// the curve parameter `P::X`.
+///
+/// This is either `P::G1Config::ENDO_COEFFS[0]` or its square, depending on
+/// which of the two eigenvalues `-x²` and `x² - 1` the curve's `λ` is.
+fn g1_endomorphism_coeff<P: Bls12Config>() -> P::Fp
+where
+    P::G1Config: GLVConfig,
+{
+    let beta = P::G1Config::ENDO_COEFFS[0];
+    let x = P::X
+        .iter()
+        .rev()
+        .fold(G1ScalarField::<P>::zero(), |acc, &limb| {
+            acc * G1ScalarField::<P>::from(1u128 << 64) + G1ScalarField::<P>::from(limb)
+        });
+    let x_squared = x.square();
+    if P::G1Config::LAMBDA == -x_squared {
+        beta
+    } else {
+        assert_eq!(
+            P::G1Config::LAMBDA,
+            x_squared - G1ScalarField::<P>::one(),
+            "the eigenvalue of the G1 endomorphism must be -x² or x² - 1"
+        );
+        beta.square()
+    }
+}
+
+/// The coefficients `(c_x, c_y)` of the endomorphism
+/// `ψ(x, y) = (c_x * x^p, c_y * y^p)` of the twist, which untwists a point,
+/// applies the Frobenius endomorphism, and twists it back. `ψ` acts on G2 as
+/// multiplication by the curve parameter `x`.
+fn g2_endomorphism_coeffs<P: Bls12Config>() -> (Fp2<P::Fp2Config>, Fp2<P::Fp2Config>) {
+    let modulus: BigUint = P::Fp::MODULUS.into();
+    let xi = <P::Fp6Config as Fp6Config>::NONRESIDUE;
+    let c_x = xi.pow(((&modulus - 1u32) / 3u32).to_u64_digits());
+    let c_y = xi.pow(((&modulus - 1u32) / 2u32).to_u64_digits());
+    match P::TWIST_TYPE {
+        TwistType::M => (c_x.inverse().unwrap(), c_y.inverse().unwrap()),
+        TwistType::D => (c_x, c_y),
+    }
+}
+
+/// Returns a `Boolean` representing whether `p` is in G1, i.e. in the
+/// prime-order subgroup.
+///
+/// Instead of multiplying by the group order, this uses the check
+/// `φ(p) = -x² * p` from Section 6 of <https://eprint.iacr.org/2021/1130>,
+/// where `φ` is the GLV endomorphism and `x` is the curve parameter, which
+/// only requires two multiplications by the much smaller `x`.
+#[tracing::instrument(target = "gr1cs")]
+pub fn g1_is_in_prime_order_subgroup<P: Bls12Config>(
+    p: &G1Var<P>,
+) -> Result<Boolean<P::Fp>, SynthesisError>
+where
+    P::G1Config: GLVConfig,
+{
+    let beta = g1_endomorphism_coeff::<P>();
+    let phi_p = G1Var::<P>::new(&p.x * beta, p.y.clone(), p.z.clone());
+    let x_squared_p = p.mul_bigint(P::X)?.mul_bigint(P::X)?;
+    Ok(phi_p.is_eq(&x_squared_p.negate()?)? & x_squared_p.is_valid_point()?)
+}
+
+/// Enforces that `p` is in G1; see [`g1_is_in_prime_order_subgroup`].
+#[tracing::instrument(target = "gr1cs")]
+pub fn g1_enforce_prime_order<P: Bls12Config>(p: &G1Var<P>) -> Result<(), SynthesisError>
+where
+    P::G1Config: GLVConfig,
+{
+    g1_is_in_prime_order_subgroup(p)?.enforce_equal(&Boolean::TRUE)
+}
+
+/// Returns a `Boolean` representing whether `p` is in G2, i.e. in the
+/// prime-order subgroup of the twist.
+///
+/// Instead of multiplying by the group order, this uses the check
+/// `ψ(p) = x * p` from Section 4 of <https://eprint.iacr.org/2021/1130>,
+/// where `ψ` is the untwist-Frobenius-twist endomorphism and `x` is the curve
+/// parameter.
+#[tracing::instrument(target = "gr1cs")]
+pub fn g2_is_in_prime_order_subgroup<P: Bls12Config>(
+    p: &G2Var<P>,
+) -> Result<Boolean<P::Fp>, SynthesisError> {
+    let (c_x, c_y) = g2_endomorphism_coeffs::<P>();
+    let psi_p = G2Var::<P>::new(
+        p.x.frobenius_map(1)? * c_x,
+        p.y.frobenius_map(1)? * c_y,
+        p.z.frobenius_map(1)?,
+    );
+    let mut x_p = p.mul_bigint(P::X)?;
+    if P::X_IS_NEGATIVE {
+        x_p = x_p.negate()?;
+    }
+    Ok(psi_p.is_eq(&x_p)? & x_p.is_valid_point()?)
+}
+
+/// Enforces that `p` is in G2; see [`g2_is_in_prime_order_subgroup`].
+#[tracing::instrument(target = "gr1cs")]
+pub fn g2_enforce_prime_order<P: Bls12Config>(p: &G2Var<P>) -> Result<(), SynthesisError> {
+    g2_is_in_prime_order_subgroup(p)?.enforce_equal(&Boolean::TRUE)
+}
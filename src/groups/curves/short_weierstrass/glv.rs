@@ -0,0 +1,177 @@
+use super::*;
+use ark_ec::scalar_mul::glv::GLVConfig;
+use ark_ff::BigInteger;
+
+impl<P, F> ProjectiveVar<P, F>
+where
+    P: GLVConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    /// Applies the GLV endomorphism `φ: (x, y) ↦ (β * x, y)` to `self`, where
+    /// `β = P::ENDO_COEFFS[0]`. This requires no constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the endomorphism of `P` is not of this form, as is the case
+    /// for curves with a `j`-invariant other than 0.
+    #[tracing::instrument(target = "gr1cs", skip(self))]
+    pub fn endomorphism(&self) -> Self {
+        let beta = Self::endomorphism_coeff();
+        Self::new(&self.x * beta, self.y.clone(), self.z.clone())
+    }
+
+    /// The coefficient `β` of the endomorphism `φ: (x, y) ↦ (β * x, y)`.
+    fn endomorphism_coeff() -> P::BaseField {
+        let beta = P::ENDO_COEFFS[0];
+        let g = P::GENERATOR;
+        assert!(
+            P::ENDO_COEFFS.len() == 1
+                && P::endomorphism_affine(&g) == SWAffine::new_unchecked(g.x * beta, g.y),
+            "only endomorphisms of the form (x, y) -> (beta * x, y) are supported"
+        );
+        beta
+    }
+
+    /// Computes `scalar * self` with the GLV method.
+    ///
+    /// `scalar` is decomposed out of circuit into `k1 + λ * k2 (mod r)`,
+    /// where `λ = P::LAMBDA` and `k1`, `k2` are signed integers of about half
+    /// the size of `r`. After enforcing the decomposition, this computes
+    /// `k1 * self + k2 * φ(self)` with a joint double-and-add over signed
+    /// digits. Each step costs one doubling and one addition of
+    /// `±(self ± φ(self))`, and there are only about half as many steps as in
+    /// [`CurveVar::scalar_mul_le`].
+    ///
+    /// `φ` only acts as multiplication by `λ` on the prime-order subgroup, so
+    /// `self` must lie in it. This holds for points allocated as witnesses via
+    /// [`AllocVar`], but not for ones allocated with
+    /// [`CurveVar::new_variable_omit_prime_order_check`].
+    #[tracing::instrument(target = "gr1cs", skip(self, scalar))]
+    pub fn scalar_mul_glv(
+        &self,
+        scalar: &EmulatedFpVar<P::ScalarField, BasePrimeField<P>>,
+    ) -> Result<Self, SynthesisError> {
+        if self.is_constant() && scalar.is_constant() {
+            return Ok(Self::constant(self.value()? * scalar.value()?));
+        }
+
+        let cs = self.cs().or(scalar.cs());
+        let mode = if scalar.is_constant() {
+            AllocationMode::Constant
+        } else {
+            AllocationMode::Witness
+        };
+        let decomposition = scalar.value().ok().map(P::scalar_decomposition);
+        let halves = [
+            decomposition.map(|(k1, _)| k1),
+            decomposition.map(|(_, k2)| k2),
+        ];
+
+        let num_bits = Self::glv_half_bits();
+        let mut signs = Vec::with_capacity(2);
+        let mut bits = Vec::with_capacity(2);
+        for half in halves {
+            let sign = Boolean::new_variable(
+                ark_relations::ns!(cs, "sign"),
+                || {
+                    half.map(|(s, _)| s)
+                        .ok_or(SynthesisError::AssignmentMissing)
+                },
+                mode,
+            )?;
+            let half_bits = half.map(|(_, k)| k.into_bigint().to_bits_le());
+            let half_bits = (0..num_bits)
+                .map(|i| {
+                    Boolean::new_variable(
+                        ark_relations::ns!(cs, "bit"),
+                        || {
+                            half_bits
+                                .as_ref()
+                                .map(|b| b[i])
+                                .ok_or(SynthesisError::AssignmentMissing)
+                        },
+                        mode,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            signs.push(sign);
+            bits.push(half_bits);
+        }
+
+        // Enforce that `scalar = ±k1 ± λ * k2 (mod r)`. The halves take the
+        // limb layout of `scalar`, so that they can be compared with it.
+        if let Some(params) = scalar.params() {
+            let mut signed_halves = Vec::with_capacity(2);
+            for (sign, bits) in signs.iter().zip(&bits) {
+                let half =
+                    EmulatedFpVar::Var(AllocatedEmulatedFpVar::from_fpvar_bits(bits, params)?);
+                signed_halves.push(sign.select(&half, &half.negate()?)?);
+            }
+            (&signed_halves[0] + &signed_halves[1] * P::LAMBDA).enforce_equal(scalar)?;
+        }
+
+        // As in `scalar_mul_le`, we replace zero by the generator so that the
+        // incomplete formulae below stay satisfiable, and select zero at the
+        // end.
+        let self_affine = self.to_affine()?;
+        let (x, y, infinity) = (self_affine.x, self_affine.y, self_affine.infinity);
+        let x = infinity.select(&F::constant(P::GENERATOR.x), &x)?;
+        let y = infinity.select(&F::constant(P::GENERATOR.y), &y)?;
+        let non_zero_self = NonZeroAffineVar::<P, F>::new(x, y);
+        let phi = NonZeroAffineVar::new(
+            &non_zero_self.x * Self::endomorphism_coeff(),
+            non_zero_self.y.clone(),
+        );
+        let p1 = non_zero_self.conditionally_negate(&!&signs[0])?;
+        let p2 = phi.conditionally_negate(&!&signs[1])?;
+
+        // With signed digits `d1, d2 ∈ {±1}`, every step adds
+        // `d1 * p1 + d2 * p2 = d1 * (p1 + d1 * d2 * p2)`, which we look up in
+        // the table `[p1 + p2, p1 - p2]`. As `φ` acts as multiplication by
+        // `λ ≠ ±1`, we have `p1 != ±p2`.
+        let table = [
+            p1.add_checked(&p2)?,
+            p1.add_checked(&NonZeroAffineVar::new(p2.x.clone(), p2.y.negate()?))?,
+        ];
+        let digits = bits
+            .iter()
+            .map(|bits| signed_odd_digit_windows(bits, 1))
+            .collect::<Vec<_>>();
+
+        let steps = digits[0]
+            .iter()
+            .zip(&digits[1])
+            .map(|(d1, d2)| {
+                let (is_negative_1, is_negative_2) = (&d1[0], &d2[0]);
+                NonZeroAffineVar::cond_neg_lookup(
+                    &[is_negative_1 ^ is_negative_2, is_negative_1.clone()],
+                    &table,
+                )
+                .map(|addend| vec![addend])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut result = double_and_add_with_offset(&steps)?;
+
+        // The digits represent `k1` and `k2` rounded up to odd integers, so we
+        // have to subtract `p1` and `p2` for even halves.
+        for (p, bits) in [p1, p2].iter().zip(&bits) {
+            result -= bits[0].select(&Self::zero(), &p.into_projective())?;
+        }
+        infinity.select(&Self::zero(), &result)
+    }
+
+    /// An upper bound on the bit length of the halves that
+    /// `P::scalar_decomposition` splits a scalar into.
+    fn glv_half_bits() -> usize {
+        // The decomposition subtracts a lattice vector that is less than one
+        // basis vector away from `(k, 0)` in each direction, so every half is
+        // smaller than twice the largest entry of the basis.
+        let max_bits = P::SCALAR_DECOMP_COEFFS
+            .iter()
+            .map(|(_, c)| c.num_bits() as usize)
+            .max()
+            .unwrap();
+        max_bits + 1
+    }
+}
@@ -10,7 +10,11 @@ use non_zero_affine::NonZeroAffineVar;
 
 use crate::{
     convert::ToConstraintFieldGadget,
-    fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
+    fields::{
+        emulated_fp::{AllocatedEmulatedFpVar, EmulatedFpVar},
+        fp::FpVar,
+    },
+    groups::signed_odd_digit_windows,
     prelude::*,
     Vec,
 };
@@ -38,6 +42,10 @@ pub mod mnt6;
 /// elliptic curve points.
 pub mod non_zero_affine;
 
+/// GLV scalar multiplication for curves with an efficiently computable
+/// endomorphism, i.e. those implementing [`GLVConfig`](ark_ec::scalar_mul::glv::GLVConfig).
+mod glv;
+
 type BasePrimeField<P> = <<P as CurveConfig>::BaseField as Field>::BasePrimeField;
 
 /// An implementation of arithmetic for Short Weierstrass curves that relies on
@@ -236,6 +244,33 @@ where
         Ok(Self::new(x, y, z))
     }
 
+    /// Computes `scalar * self` for a constant `scalar` by double-and-add with
+    /// the complete formulae, so that the result is correct for every point
+    /// on the curve, including points outside the prime-order subgroup.
+    #[tracing::instrument(target = "gr1cs", skip(self, scalar))]
+    pub(crate) fn mul_bigint(&self, scalar: impl AsRef<[u64]>) -> Result<Self, SynthesisError> {
+        let mut result = Self::zero();
+        for b in BitIteratorBE::without_leading_zeros(scalar) {
+            result.double_in_place()?;
+
+            if b {
+                result += self;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns a `Boolean` representing whether `self` is a valid projective
+    /// point, i.e. is not `(0 : 0 : 0)`.
+    ///
+    /// `(0 : 0 : 0)` satisfies the curve equation, and is what the complete
+    /// formulae output on their exceptional inputs. For points on the curve,
+    /// `z = 0` implies `x = 0`, so it suffices to check `y` and `z`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub(crate) fn is_valid_point(&self) -> Result<Boolean<BasePrimeField<P>>, SynthesisError> {
+        Ok(!(self.y.is_zero()? & self.z.is_zero()?))
+    }
+
     /// Mixed addition, which is useful when `other = (x2, y2)` is known to have
     /// z = 1.
     #[tracing::instrument(target = "gr1cs", skip(self, other))]
@@ -369,6 +404,91 @@ where
         }
         Ok(())
     }
+
+    /// Computes `bits * self`, where `bits` is a little-endian `Boolean`
+    /// representation of a scalar, with a signed fixed-window method.
+    ///
+    /// The scalar is recoded into signed odd digits of `window_bits` bits
+    /// each, and the table of odd multiples `[self, 3 * self, ...,
+    /// (2^window_bits - 1) * self]` is computed in-circuit. Every window then
+    /// costs `window_bits` doublings, one lookup into the table and one
+    /// addition. For `window_bits = 3`, this requires fewer constraints than
+    /// [`CurveVar::scalar_mul_le`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_bits < 2`, or if `window_bits + 3` exceeds
+    /// `P::ScalarField::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs", skip(self, bits))]
+    pub fn windowed_scalar_mul_le<'a>(
+        &self,
+        bits: impl Iterator<Item = &'a Boolean<BasePrimeField<P>>>,
+        window_bits: usize,
+    ) -> Result<Self, SynthesisError> {
+        let scalar_modulus_bits = <P::ScalarField as PrimeField>::MODULUS_BIT_SIZE as usize;
+        assert!(window_bits >= 2 && window_bits + 3 <= scalar_modulus_bits);
+
+        let bits = bits.cloned().collect::<Vec<_>>();
+        if bits.is_empty() {
+            return Ok(Self::zero());
+        }
+        if self.is_constant() && self.value()?.is_zero() {
+            return Ok(self.clone());
+        }
+        // As in `scalar_mul_le`, we replace zero by the generator so that the
+        // incomplete formulae below stay satisfiable, and select zero at the
+        // end.
+        let self_affine = self.to_affine()?;
+        let (x, y, infinity) = (self_affine.x, self_affine.y, self_affine.infinity);
+        let x = infinity.select(&F::constant(P::GENERATOR.x), &x)?;
+        let y = infinity.select(&F::constant(P::GENERATOR.y), &y)?;
+        let non_zero_self = NonZeroAffineVar::new(x, y);
+
+        // The odd multiples `(2i + 1) * self` are all smaller than `r / 2`,
+        // so the additions of `2 * self` do not hit an exceptional case.
+        let double = non_zero_self.double()?;
+        let mut table = ark_std::vec![non_zero_self.clone()];
+        for _ in 1..(1 << (window_bits - 1)) {
+            let next = table.last().unwrap().add_unchecked(&double)?;
+            table.push(next);
+        }
+
+        // After processing `j` windows, the accumulator is `m * self` for some
+        // odd `0 < m < 2^(window_bits * j)`. While this bound is at most
+        // `2^(scalar_modulus_bits - 2)`, we can use the incomplete formulae:
+        // * The doublings of `m * self` are non-zero, as `0 < 2^i * m < r`.
+        // * `double_and_add_unchecked(a, t)` computes `(a + t) + a`, where
+        //   `a = e * self` for an even `0 < e < r / 2`, and `t = d * self` for
+        //   an odd digit `d`. As `e ∓ d` is odd and smaller than `r`, we have
+        //   `a != ±t`. Furthermore, `2a + t` is the next accumulator, which is
+        //   non-zero, so `a + t != -a`.
+        let windows = signed_odd_digit_windows(&bits, window_bits);
+        let num_affine_windows = ((scalar_modulus_bits - 2) / window_bits).min(windows.len());
+        let (affine_windows, proj_windows) = windows.split_at(num_affine_windows);
+
+        let mut accumulator = NonZeroAffineVar::cond_neg_lookup(&affine_windows[0], &table)?;
+        for window in &affine_windows[1..] {
+            for _ in 1..window_bits {
+                accumulator.double_in_place()?;
+            }
+            let addend = NonZeroAffineVar::cond_neg_lookup(window, &table)?;
+            accumulator = accumulator.double_and_add_unchecked(&addend)?;
+        }
+
+        // We finish off the remaining windows with the complete formulae.
+        let mut result = accumulator.into_projective();
+        for window in proj_windows {
+            for _ in 0..window_bits {
+                result.double_in_place()?;
+            }
+            result = result.add_mixed(&NonZeroAffineVar::cond_neg_lookup(window, &table)?)?;
+        }
+
+        // The windows represent the scalar rounded up to an odd integer, so we
+        // have to subtract `self` if the scalar is even.
+        let result = bits[0].select(&result, &(&result - non_zero_self.into_projective()))?;
+        infinity.select(&Self::zero(), &result)
+    }
 }
 
 impl<P, F> CurveVar<SWProjective<P>, BasePrimeField<P>> for ProjectiveVar<P, F>
@@ -430,24 +550,24 @@ where
     /// Enforce that `self` is in the prime-order subgroup.
     ///
     /// Does so by multiplying by the prime order, and checking that the result
-    /// is unchanged.
-    // TODO: at the moment this doesn't work, because the addition and doubling
-    // formulae are incomplete for even-order points.
+    /// is zero; see [`Self::is_in_prime_order_subgroup`].
     #[tracing::instrument(target = "gr1cs")]
     fn enforce_prime_order(&self) -> Result<(), SynthesisError> {
-        unimplemented!("cannot enforce prime order");
-        // let r_minus_1 = (-P::ScalarField::one()).into_bigint();
-
-        // let mut result = Self::zero();
-        // for b in BitIteratorBE::without_leading_zeros(r_minus_1) {
-        //     result.double_in_place()?;
-
-        //     if b {
-        //         result += self;
-        //     }
-        // }
-        // self.negate()?.enforce_equal(&result)?;
-        // Ok(())
+        self.is_in_prime_order_subgroup()?
+            .enforce_equal(&Boolean::TRUE)
+    }
+
+    /// Returns a `Boolean` representing whether `self` is in the prime-order
+    /// subgroup.
+    ///
+    /// Does so by multiplying by the prime order, and checking that the result
+    /// is zero. On curves with points of even order, the complete formulae
+    /// output `(0 : 0 : 0)` for some inputs outside the subgroup, which is
+    /// why the result must also be a valid point.
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_in_prime_order_subgroup(&self) -> Result<Boolean<BasePrimeField<P>>, SynthesisError> {
+        let result = self.mul_bigint(P::ScalarField::MODULUS)?;
+        Ok(result.is_zero()? & result.is_valid_point()?)
     }
 
     #[inline]
@@ -575,6 +695,202 @@ where
         *self += Self::constant(base).scalar_mul_le(bits.iter())?;
         Ok(())
     }
+
+    /// Computes `Σⱼ(scalarⱼ * baseⱼ)` with Straus's method, sharing the
+    /// doublings among all terms.
+    ///
+    /// The scalars are recoded into signed digits `±1`, so that every base is
+    /// added or subtracted in every step, and the accumulator is kept in
+    /// affine coordinates. It starts at an offset that the prover picks such
+    /// that the incomplete formulae never fail, whatever the bases.
+    #[tracing::instrument(target = "gr1cs", skip(bases, scalars))]
+    fn multi_scalar_mul_le(
+        bases: &[Self],
+        scalars: &[Vec<Boolean<BasePrimeField<P>>>],
+    ) -> Result<Self, SynthesisError> {
+        assert_eq!(bases.len(), scalars.len());
+        let num_bits = scalars.iter().map(Vec::len).max().unwrap_or(0);
+        if bases.is_empty() || num_bits == 0 {
+            return Ok(Self::zero());
+        }
+
+        // The incomplete formulae do not support zero, so we replace zero
+        // bases by the generator, and their scalars by zero. The signed digits
+        // of a zero scalar represent 1, so that the generator cancels out with
+        // the final correction below.
+        let mut non_zero_bases = Vec::with_capacity(bases.len());
+        let mut all_bits = Vec::with_capacity(bases.len());
+        for (base, bits) in bases.iter().zip(scalars) {
+            let base = base.to_affine()?;
+            let x = base
+                .infinity
+                .select(&F::constant(P::GENERATOR.x), &base.x)?;
+            let y = base
+                .infinity
+                .select(&F::constant(P::GENERATOR.y), &base.y)?;
+            non_zero_bases.push(NonZeroAffineVar::new(x, y));
+
+            let is_non_zero = !base.infinity;
+            let mut bits = bits.iter().map(|b| b & &is_non_zero).collect::<Vec<_>>();
+            bits.resize(num_bits, Boolean::FALSE);
+            all_bits.push(bits);
+        }
+        let digits = all_bits
+            .iter()
+            .map(|bits| signed_odd_digit_windows(bits, 1))
+            .collect::<Vec<_>>();
+
+        let steps = (0..num_bits)
+            .map(|i| {
+                non_zero_bases
+                    .iter()
+                    .zip(&digits)
+                    .map(|(base, base_digits)| {
+                        NonZeroAffineVar::cond_neg_lookup(
+                            &base_digits[i],
+                            ark_std::slice::from_ref(base),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut result = double_and_add_with_offset(&steps)?;
+
+        // The digits represent the scalars rounded up to odd integers, so we
+        // have to subtract the bases of even scalars.
+        for (base, bits) in non_zero_bases.iter().zip(&all_bits) {
+            result -= bits[0].select(&Self::zero(), &base.into_projective())?;
+        }
+        Ok(result)
+    }
+}
+
+/// Computes `Σᵢ 2^(n - 1 - i) * Σⱼ steps[i][j]`, where `n = steps.len()`, with
+/// a double-and-add over affine points.
+///
+/// The affine formulae are incomplete: they fail if the accumulator is zero,
+/// of order two before a doubling, or equal to ± the point being added. The
+/// accumulator therefore starts at an offset `c * G`, where `G` is
+/// [`msm_offset_point`] and the prover picks the smallest `c > 0` that avoids
+/// these cases for the given points. Each doubling rules out at most four
+/// values of `c`, and each addition at most three, so we allocate just enough
+/// bits for `c` to always find one. `add_checked` makes the constraints
+/// unsatisfiable rather than unsound for any other `c`.
+fn double_and_add_with_offset<P, F>(
+    steps: &[Vec<NonZeroAffineVar<P, F>>],
+) -> Result<ProjectiveVar<P, F>, SynthesisError>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    // We read the coordinates directly, as the points need not lie in the
+    // prime-order subgroup.
+    let step_values = steps
+        .iter()
+        .map(|step| {
+            step.iter()
+                .map(|p| Ok(SWAffine::new_unchecked(p.x.value()?, p.y.value()?)))
+                .collect::<Result<Vec<_>, SynthesisError>>()
+        })
+        .collect::<Result<Vec<_>, _>>();
+    if steps.iter().flatten().all(|p| p.is_constant()) {
+        let mut result = SWProjective::<P>::zero();
+        for step in step_values? {
+            result.double_in_place();
+            for p in step {
+                result += p;
+            }
+        }
+        return Ok(ProjectiveVar::constant(result));
+    }
+
+    let num_excluded: usize = steps.iter().map(|step| 4 + 3 * step.len()).sum();
+    let num_offset_bits = ark_std::log2(num_excluded + 2) as usize;
+    let multiplier = step_values.ok().map(|steps| offset_multiplier(&steps));
+    let cs = steps
+        .iter()
+        .flatten()
+        .fold(ConstraintSystemRef::None, |cs, p| cs.or(p.cs()));
+    let offset_bits = (0..num_offset_bits)
+        .map(|i| {
+            Boolean::new_witness(ark_relations::ns!(cs, "offset bit"), || {
+                multiplier
+                    .map(|c| (c >> i) & 1 == 1)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Enforcing that the offset is non-zero rules out `c = 0`, and ensures
+    // that its affine coordinates are those of a point on the curve.
+    let generator = msm_offset_point::<P>();
+    let offset = ProjectiveVar::<P, F>::constant(generator.into())
+        .scalar_mul_le(offset_bits.iter())?
+        .to_affine()?;
+    offset.infinity.enforce_equal(&Boolean::FALSE)?;
+    let mut accumulator = NonZeroAffineVar::new(offset.x, offset.y);
+    for step in steps {
+        accumulator.double_in_place()?;
+        for addend in step {
+            accumulator = accumulator.add_checked(addend)?;
+        }
+    }
+
+    // Remove the offset, which has been doubled `n` times.
+    let mut doubled_generator = SWProjective::from(generator);
+    for _ in 0..steps.len() {
+        doubled_generator.double_in_place();
+    }
+    let doubled_offset =
+        ProjectiveVar::constant(doubled_generator).scalar_mul_le(offset_bits.iter())?;
+    Ok(accumulator.into_projective() - doubled_offset)
+}
+
+/// The smallest `c > 0` for which the double-and-add of
+/// [`double_and_add_with_offset`] never hits an exceptional case of the affine
+/// formulae when started at `c * msm_offset_point()`.
+fn offset_multiplier<P: SWCurveConfig>(steps: &[Vec<SWAffine<P>>]) -> u64 {
+    let avoids_exceptions = |mut accumulator: SWAffine<P>| {
+        for step in steps {
+            if accumulator.is_zero() || accumulator.y.is_zero() {
+                return false;
+            }
+            accumulator = SWProjective::from(accumulator).double().into_affine();
+            for addend in step {
+                if accumulator.is_zero() || accumulator.x == addend.x {
+                    return false;
+                }
+                accumulator = (accumulator + addend).into_affine();
+            }
+        }
+        true
+    };
+    let generator = msm_offset_point::<P>();
+    let mut offset = SWProjective::<P>::zero();
+    (1..)
+        .find(|_| {
+            offset += generator;
+            avoids_exceptions(offset.into_affine())
+        })
+        .unwrap()
+}
+
+/// A point of the prime-order subgroup that has no known relation to any other
+/// point, found by try-and-increment from a fixed seed. Small multiples of it
+/// offset the accumulator in [`double_and_add_with_offset`].
+fn msm_offset_point<P: SWCurveConfig>() -> SWAffine<P> {
+    let seed = BasePrimeField::<P>::from_le_bytes_mod_order(b"ark-r1cs-std msm offset");
+    let mut x = P::BaseField::from_base_prime_field(seed);
+    loop {
+        if let Some(point) = SWAffine::<P>::get_point_from_x_unchecked(x, false) {
+            let point = point.mul_by_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+        x += P::BaseField::one();
+    }
 }
 
 impl<P, F> ToConstraintFieldGadget<BasePrimeField<P>> for ProjectiveVar<P, F>
@@ -751,6 +1067,22 @@ where
     }
 }
 
+impl<P, F> TableLookupGadget<BasePrimeField<P>> for ProjectiveVar<P, F>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_negate(
+        &self,
+        cond: &Boolean<BasePrimeField<P>>,
+    ) -> Result<Self, SynthesisError> {
+        let y = cond.select(&self.y.negate()?, &self.y)?;
+        Ok(Self::new(self.x.clone(), y, self.z.clone()))
+    }
+}
+
 impl<P, F> EqGadget<BasePrimeField<P>> for ProjectiveVar<P, F>
 where
     P: SWCurveConfig,
@@ -979,20 +1311,34 @@ where
 
 #[cfg(test)]
 mod test_sw_curve {
+    use super::{msm_offset_point, BasePrimeField};
     use crate::{
-        alloc::AllocVar,
+        alloc::{AllocVar, AllocationMode},
+        boolean::Boolean,
         convert::ToBitsGadget,
         eq::EqGadget,
-        fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
-        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        fields::{
+            emulated_fp::{EmulatedFpVar, NonNativeFieldConfig},
+            fp::FpVar,
+            fp2::Fp2Var,
+            FieldOpsBounds, FieldVar,
+        },
+        groups::{
+            bls12::{g1_is_in_prime_order_subgroup, g2_is_in_prime_order_subgroup},
+            curves::short_weierstrass::ProjectiveVar,
+            CurveVar,
+        },
+        test_utils::constraints_added,
+        GR1CSVar,
     };
     use ark_ec::{
-        short_weierstrass::{Projective, SWCurveConfig},
+        scalar_mul::glv::GLVConfig,
+        short_weierstrass::{Affine, Projective, SWCurveConfig},
         CurveGroup,
     };
-    use ark_ff::PrimeField;
+    use ark_ff::{AdditiveGroup, BigInteger, One, PrimeField};
     use ark_relations::gr1cs::{ConstraintSystem, Result};
-    use ark_std::UniformRand;
+    use ark_std::{rand::Rng, vec::Vec, UniformRand};
     use num_traits::Zero;
 
     fn zero_point_scalar_mul_satisfied<G>() -> Result<bool>
@@ -1033,4 +1379,348 @@ mod test_sw_curve {
         assert!(zero_point_scalar_mul_satisfied::<ark_mnt6_298::G1Projective>().unwrap());
         assert!(zero_point_scalar_mul_satisfied::<ark_bn254::G1Projective>().unwrap());
     }
+
+    fn random_point_on_curve<P: SWCurveConfig>(rng: &mut impl Rng) -> Affine<P> {
+        loop {
+            let x = P::BaseField::rand(rng);
+            if let Some(point) = Affine::<P>::get_point_from_x_unchecked(x, rng.gen()) {
+                return point;
+            }
+        }
+    }
+
+    fn subgroup_check_matches<P, F>(
+        is_in_subgroup: impl Fn(&ProjectiveVar<P, F>) -> Result<Boolean<BasePrimeField<P>>>,
+    ) where
+        P: SWCurveConfig,
+        F: FieldVar<P::BaseField, BasePrimeField<P>>,
+        for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+    {
+        let mut rng = ark_std::test_rng();
+        let in_subgroup = Projective::<P>::rand(&mut rng).into_affine();
+        let on_curve = random_point_on_curve::<P>(&mut rng);
+        assert!(!P::is_in_correct_subgroup_assuming_on_curve(&on_curve));
+
+        for point in [Affine::<P>::identity(), in_subgroup, on_curve] {
+            let cs = ConstraintSystem::new_ref();
+            let var = ProjectiveVar::<P, F>::new_variable_omit_prime_order_check(
+                cs.clone(),
+                || Ok(point.into()),
+                AllocationMode::Witness,
+            )
+            .unwrap();
+            let result = is_in_subgroup(&var).unwrap();
+            assert_eq!(
+                result.value().unwrap(),
+                P::is_in_correct_subgroup_assuming_on_curve(&point)
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_prime_order_check() {
+        use ark_bls12_381::{g1, g2, Config, Fq, Fq2Config};
+        subgroup_check_matches::<g1::Config, FpVar<Fq>>(|p| p.is_in_prime_order_subgroup());
+        subgroup_check_matches::<g2::Config, Fp2Var<Fq2Config>>(|p| p.is_in_prime_order_subgroup());
+
+        // Enforcing the check on a point outside the subgroup is unsatisfiable.
+        let cs = ConstraintSystem::new_ref();
+        let point = random_point_on_curve::<g1::Config>(&mut ark_std::test_rng());
+        let var = ProjectiveVar::<g1::Config, FpVar<Fq>>::new_variable_omit_prime_order_check(
+            cs.clone(),
+            || Ok(point.into()),
+            AllocationMode::Witness,
+        )
+        .unwrap();
+        var.enforce_prime_order().unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        subgroup_check_matches::<g1::Config, FpVar<Fq>>(g1_is_in_prime_order_subgroup::<Config>);
+        subgroup_check_matches::<g2::Config, Fp2Var<Fq2Config>>(
+            g2_is_in_prime_order_subgroup::<Config>,
+        );
+
+        // The endomorphism-based checks are cheaper than the generic ones.
+        assert!(
+            subgroup_check_cost::<g1::Config, FpVar<Fq>>(g1_is_in_prime_order_subgroup::<Config>)
+                < subgroup_check_cost::<g1::Config, FpVar<Fq>>(|p| p.is_in_prime_order_subgroup())
+        );
+        assert!(
+            subgroup_check_cost::<g2::Config, Fp2Var<Fq2Config>>(
+                g2_is_in_prime_order_subgroup::<Config>
+            ) < subgroup_check_cost::<g2::Config, Fp2Var<Fq2Config>>(|p| {
+                p.is_in_prime_order_subgroup()
+            })
+        );
+    }
+
+    fn subgroup_check_cost<P, F>(
+        is_in_subgroup: impl Fn(&ProjectiveVar<P, F>) -> Result<Boolean<BasePrimeField<P>>>,
+    ) -> usize
+    where
+        P: SWCurveConfig,
+        F: FieldVar<P::BaseField, BasePrimeField<P>>,
+        for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+    {
+        let cs = ConstraintSystem::new_ref();
+        let var = ProjectiveVar::<P, F>::new_variable_omit_prime_order_check(
+            cs.clone(),
+            || Ok(Projective::<P>::rand(&mut ark_std::test_rng())),
+            AllocationMode::Witness,
+        )
+        .unwrap();
+        constraints_added(&cs, || is_in_subgroup(&var).unwrap()).1
+    }
+
+    #[test]
+    fn test_bls12_377_prime_order_check() {
+        use ark_bls12_377::{g1, g2, Config, Fq, Fq2Config};
+        subgroup_check_matches::<g1::Config, FpVar<Fq>>(|p| p.is_in_prime_order_subgroup());
+        subgroup_check_matches::<g1::Config, FpVar<Fq>>(g1_is_in_prime_order_subgroup::<Config>);
+        subgroup_check_matches::<g2::Config, Fp2Var<Fq2Config>>(
+            g2_is_in_prime_order_subgroup::<Config>,
+        );
+    }
+
+    fn glv_scalar_mul_matches<P, F>()
+    where
+        P: GLVConfig,
+        F: FieldVar<P::BaseField, BasePrimeField<P>>,
+        for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+    {
+        let mut rng = ark_std::test_rng();
+        for point in [Projective::<P>::rand(&mut rng), Projective::<P>::zero()] {
+            for scalar in [
+                P::ScalarField::rand(&mut rng),
+                P::ScalarField::zero(),
+                -P::ScalarField::one(),
+            ] {
+                let cs = ConstraintSystem::new_ref();
+                let point_var =
+                    ProjectiveVar::<P, F>::new_witness(cs.clone(), || Ok(point)).unwrap();
+                let scalar_var = EmulatedFpVar::new_witness(cs.clone(), || Ok(scalar)).unwrap();
+                let result = point_var.scalar_mul_glv(&scalar_var).unwrap();
+                assert_eq!(result.value().unwrap(), point * scalar);
+
+                let result = point_var
+                    .scalar_mul_glv(&EmulatedFpVar::constant(scalar))
+                    .unwrap();
+                assert_eq!(result.value().unwrap(), point * scalar);
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_glv_scalar_mul() {
+        glv_scalar_mul_matches::<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>();
+        glv_scalar_mul_matches::<ark_bls12_381::g2::Config, Fp2Var<ark_bls12_381::Fq2Config>>();
+        glv_scalar_mul_matches::<ark_bn254::g1::Config, FpVar<ark_bn254::Fq>>();
+
+        // Compare the number of constraints with the bitwise method, including
+        // the cost of the decomposition and of the bits of the scalar.
+        use ark_bls12_381::{g1::Config, Fq, Fr};
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let point_var = ProjectiveVar::<Config, FpVar<Fq>>::new_witness(cs.clone(), || {
+            Ok(Projective::rand(&mut rng))
+        })
+        .unwrap();
+        let scalar =
+            EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let (bitwise, bitwise_cost) = constraints_added(&cs, || {
+            point_var
+                .scalar_mul_le(scalar.to_bits_le().unwrap().iter())
+                .unwrap()
+        });
+        let (glv, glv_cost) = constraints_added(&cs, || point_var.scalar_mul_glv(&scalar).unwrap());
+        assert!(glv_cost < bitwise_cost);
+        glv.enforce_equal(&bitwise).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // Scalars with a limb layout other than the default one are supported.
+        let params = NonNativeFieldConfig {
+            num_limbs: 8,
+            bits_per_limb: 32,
+        };
+        let scalar = Fr::rand(&mut rng);
+        let scalar_var = EmulatedFpVar::<Fr, Fq>::new_variable_with_params(
+            cs.clone(),
+            || Ok(scalar),
+            AllocationMode::Witness,
+            params,
+        )
+        .unwrap();
+        let result = point_var.scalar_mul_glv(&scalar_var).unwrap();
+        assert_eq!(result.value().unwrap(), point_var.value().unwrap() * scalar);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_windowed_scalar_mul() {
+        use ark_bls12_381::{g1::Config, Fq, Fr};
+        let mut rng = ark_std::test_rng();
+        for point in [Projective::<Config>::rand(&mut rng), Projective::zero()] {
+            for scalar in [Fr::rand(&mut rng), Fr::zero(), Fr::one(), -Fr::one()] {
+                for window_bits in 2..=4 {
+                    let cs = ConstraintSystem::new_ref();
+                    let point_var =
+                        ProjectiveVar::<Config, FpVar<Fq>>::new_witness(cs.clone(), || Ok(point))
+                            .unwrap();
+                    let bits = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(scalar))
+                        .unwrap()
+                        .to_bits_le()
+                        .unwrap();
+                    let result = point_var
+                        .windowed_scalar_mul_le(bits.iter(), window_bits)
+                        .unwrap();
+                    assert_eq!(result.value().unwrap(), point * scalar);
+
+                    let constant_bits = bits
+                        .iter()
+                        .map(|b| Boolean::constant(b.value().unwrap()))
+                        .collect::<Vec<_>>();
+                    let result = point_var
+                        .windowed_scalar_mul_le(constant_bits.iter(), window_bits)
+                        .unwrap();
+                    assert_eq!(result.value().unwrap(), point * scalar);
+                    assert!(cs.is_satisfied().unwrap());
+                }
+            }
+        }
+
+        // Compare the number of constraints with the bitwise method.
+        let cs = ConstraintSystem::new_ref();
+        let point_var = ProjectiveVar::<Config, FpVar<Fq>>::new_witness(cs.clone(), || {
+            Ok(Projective::rand(&mut rng))
+        })
+        .unwrap();
+        let bits = Vec::<Boolean<Fq>>::new_witness(cs.clone(), || {
+            let mut bits = Fr::rand(&mut rng).into_bigint().to_bits_le();
+            bits.truncate(Fr::MODULUS_BIT_SIZE as usize);
+            Ok(bits)
+        })
+        .unwrap();
+        let (bitwise, bitwise_cost) =
+            constraints_added(&cs, || point_var.scalar_mul_le(bits.iter()).unwrap());
+        let (windowed, windowed_cost) = constraints_added(&cs, || {
+            point_var.windowed_scalar_mul_le(bits.iter(), 3).unwrap()
+        });
+        assert!(windowed_cost < bitwise_cost);
+        windowed.enforce_equal(&bitwise).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_multi_scalar_mul() {
+        use ark_bls12_381::{g1::Config, Fq, Fr};
+        type G1Var = ProjectiveVar<Config, FpVar<Fq>>;
+        let mut rng = ark_std::test_rng();
+
+        // Repeated, negated and zero bases are all valid inputs.
+        let p = Projective::<Config>::rand(&mut rng);
+        let q = Projective::<Config>::rand(&mut rng);
+        let bases = [p, q, Projective::zero(), p, -p, q];
+        let scalars = [
+            Fr::rand(&mut rng),
+            Fr::rand(&mut rng),
+            Fr::rand(&mut rng),
+            Fr::zero(),
+            Fr::one(),
+            Fr::rand(&mut rng),
+        ];
+        let cs = ConstraintSystem::new_ref();
+        let mut base_vars = bases[..5]
+            .iter()
+            .map(|b| G1Var::new_witness(cs.clone(), || Ok(*b)).unwrap())
+            .collect::<Vec<_>>();
+        base_vars.push(G1Var::constant(bases[5]));
+        let mut scalar_vars = scalars
+            .iter()
+            .map(|s| {
+                EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(*s))
+                    .unwrap()
+                    .to_bits_le()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        // Shorter scalars are padded with zeros.
+        scalar_vars[4].truncate(1);
+        let result = G1Var::multi_scalar_mul_le(&base_vars, &scalar_vars).unwrap();
+        let expected: Projective<Config> = bases.iter().zip(&scalars).map(|(b, s)| *b * s).sum();
+        assert_eq!(result.value().unwrap(), expected);
+        assert!(cs.is_satisfied().unwrap());
+
+        // Compare the number of constraints with separate scalar multiplications.
+        let cs = ConstraintSystem::new_ref();
+        let base_vars = (0..4)
+            .map(|_| G1Var::new_witness(cs.clone(), || Ok(Projective::rand(&mut rng))).unwrap())
+            .collect::<Vec<_>>();
+        let scalar_vars = (0..4)
+            .map(|_| {
+                Vec::<Boolean<Fq>>::new_witness(cs.clone(), || {
+                    let mut bits = Fr::rand(&mut rng).into_bigint().to_bits_le();
+                    bits.truncate(Fr::MODULUS_BIT_SIZE as usize);
+                    Ok(bits)
+                })
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let (separate, separate_cost) = constraints_added(&cs, || {
+            let mut separate = G1Var::zero();
+            for (base, bits) in base_vars.iter().zip(&scalar_vars) {
+                separate += base.scalar_mul_le(bits.iter()).unwrap();
+            }
+            separate
+        });
+        let (multi, multi_cost) = constraints_added(&cs, || {
+            G1Var::multi_scalar_mul_le(&base_vars, &scalar_vars).unwrap()
+        });
+        assert!(multi_cost < separate_cost);
+        multi.enforce_equal(&separate).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_multi_scalar_mul_offset_multiples() {
+        use ark_bls12_381::{g1::Config, Fq, Fr};
+        type G1Var = ProjectiveVar<Config, FpVar<Fq>>;
+        let mut rng = ark_std::test_rng();
+
+        // Bases that are multiples of `msm_offset_point` hit the exceptional
+        // cases of the affine formulae for some offsets, e.g. twice the point
+        // itself equals the accumulator after the first doubling if the
+        // accumulator starts at the point.
+        let offset = Projective::<Config>::from(msm_offset_point::<Config>());
+        for (bases, scalars) in [
+            (vec![offset], vec![Fr::rand(&mut rng)]),
+            (vec![offset.double()], vec![Fr::rand(&mut rng)]),
+            (vec![-offset.double()], vec![Fr::rand(&mut rng)]),
+            (
+                vec![offset.double(), offset],
+                vec![Fr::rand(&mut rng), Fr::from(2u64)],
+            ),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let base_vars = bases
+                .iter()
+                .map(|b| G1Var::new_witness(cs.clone(), || Ok(*b)).unwrap())
+                .collect::<Vec<_>>();
+            let scalar_vars = scalars
+                .iter()
+                .map(|s| {
+                    EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(*s))
+                        .unwrap()
+                        .to_bits_le()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            let result = G1Var::multi_scalar_mul_le(&base_vars, &scalar_vars).unwrap();
+            let expected: Projective<Config> =
+                bases.iter().zip(&scalars).map(|(b, s)| *b * s).sum();
+            assert_eq!(result.value().unwrap(), expected);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
 }
@@ -66,6 +66,31 @@ where
         }
     }
 
+    /// Performs an addition, enforcing that `other != ±self`.
+    ///
+    /// Unlike [`Self::add_unchecked`], this is sound for all inputs: if
+    /// `other = ±self`, the resulting constraints are unsatisfiable.
+    #[tracing::instrument(target = "gr1cs", skip(self, other))]
+    pub fn add_checked(&self, other: &Self) -> Result<Self, SynthesisError> {
+        if [self, other].is_constant() {
+            if self.x.value()? == other.x.value()? {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            self.add_unchecked(other)
+        } else {
+            let (x1, y1) = (&self.x, &self.y);
+            let (x2, y2) = (&other.x, &other.y);
+            // The same formulae as in `add_unchecked`, except that `mul_by_inverse`
+            // enforces that `x1 != x2`, i.e. that `other != ±self`.
+            let numerator = y2 - y1;
+            let denominator = x2 - x1;
+            let lambda = numerator.mul_by_inverse(&denominator)?;
+            let x3 = lambda.square()? - x1 - x2;
+            let y3 = lambda * &(x1 - &x3) - y1;
+            Ok(Self::new(x3, y3))
+        }
+    }
+
     /// Doubles `self`. As this is a prime order curve point,
     /// the output is guaranteed to not be the point at infinity.
     #[tracing::instrument(target = "gr1cs", skip(self))]
@@ -175,6 +200,22 @@ where
     }
 }
 
+impl<P, F> TableLookupGadget<<P::BaseField as Field>::BasePrimeField> for NonZeroAffineVar<P, F>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, <P::BaseField as Field>::BasePrimeField>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_negate(
+        &self,
+        cond: &Boolean<<P::BaseField as Field>::BasePrimeField>,
+    ) -> Result<Self, SynthesisError> {
+        let y = cond.select(&self.y.negate()?, &self.y)?;
+        Ok(Self::new(self.x.clone(), y))
+    }
+}
+
 impl<P, F> EqGadget<<P::BaseField as Field>::BasePrimeField> for NonZeroAffineVar<P, F>
 where
     P: SWCurveConfig,
@@ -8,7 +8,10 @@ use ark_ec::{
 use ark_ff::{BitIteratorBE, Field, One, PrimeField, Zero};
 use ark_relations::gr1cs::{ConstraintSystemRef, Namespace, SynthesisError};
 
-use crate::{convert::ToConstraintFieldGadget, fields::emulated_fp::EmulatedFpVar, prelude::*};
+use crate::{
+    convert::ToConstraintFieldGadget, fields::emulated_fp::EmulatedFpVar,
+    groups::signed_odd_digit_windows, prelude::*,
+};
 
 use crate::fields::fp::FpVar;
 use ark_std::{borrow::Borrow, marker::PhantomData, ops::Mul, vec::Vec};
@@ -375,6 +378,62 @@ where
     }
 }
 
+impl<P, F> AffineVar<P, F>
+where
+    P: TECurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>
+        + TwoBitLookupGadget<BasePrimeField<P>, TableConstant = P::BaseField>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    /// Computes `bits * self`, where `bits` is a little-endian `Boolean`
+    /// representation of a scalar, with a signed fixed-window method.
+    ///
+    /// The scalar is recoded into signed odd digits of `window_bits` bits
+    /// each, and the table of odd multiples `[self, 3 * self, ...,
+    /// (2^window_bits - 1) * self]` is computed in-circuit. Every window then
+    /// costs `window_bits` doublings, one lookup into the table and one
+    /// addition. For `window_bits = 3`, this requires fewer constraints than
+    /// [`CurveVar::scalar_mul_le`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_bits == 0`.
+    #[tracing::instrument(target = "gr1cs", skip(self, bits))]
+    pub fn windowed_scalar_mul_le<'a>(
+        &self,
+        bits: impl Iterator<Item = &'a Boolean<BasePrimeField<P>>>,
+        window_bits: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert!(window_bits > 0);
+        let bits = bits.cloned().collect::<Vec<_>>();
+        if bits.is_empty() {
+            return Ok(Self::zero());
+        }
+
+        // Like `CurveVar::scalar_mul_le`, this relies on the addition formulae
+        // being complete, so there are no exceptional cases to avoid.
+        let double = self.double()?;
+        let mut table = ark_std::vec![self.clone()];
+        for _ in 1..(1 << (window_bits - 1)) {
+            let next = table.last().unwrap() + &double;
+            table.push(next);
+        }
+
+        let windows = signed_odd_digit_windows(&bits, window_bits);
+        let mut result = Self::cond_neg_lookup(&windows[0], &table)?;
+        for window in &windows[1..] {
+            for _ in 0..window_bits {
+                result.double_in_place()?;
+            }
+            result += Self::cond_neg_lookup(window, &table)?;
+        }
+
+        // The windows represent the scalar rounded up to an odd integer, so we
+        // have to subtract `self` if the scalar is even.
+        bits[0].select(&result, &(&result - self))
+    }
+}
+
 impl<P, F> GR1CSVar<BasePrimeField<P>> for AffineVar<P, F>
 where
     P: TECurveConfig,
@@ -848,6 +907,22 @@ where
     }
 }
 
+impl<P, F> TableLookupGadget<BasePrimeField<P>> for AffineVar<P, F>
+where
+    P: TECurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'b> &'b F: FieldOpsBounds<'b, P::BaseField, F>,
+{
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_negate(
+        &self,
+        cond: &Boolean<BasePrimeField<P>>,
+    ) -> Result<Self, SynthesisError> {
+        let x = cond.select(&self.x.negate()?, &self.x)?;
+        Ok(Self::new(x, self.y.clone()))
+    }
+}
+
 impl<P, F> EqGadget<BasePrimeField<P>> for AffineVar<P, F>
 where
     P: TECurveConfig,
@@ -931,3 +1006,102 @@ where
         Ok(x_bytes)
     }
 }
+
+#[cfg(test)]
+mod test_te_curve {
+    use super::AffineVar;
+    use crate::{
+        alloc::AllocVar, boolean::Boolean, eq::EqGadget, fields::fp::FpVar, groups::CurveVar,
+        test_utils::constraints_added, GR1CSVar,
+    };
+    use ark_ec::twisted_edwards::Projective;
+    use ark_ed_on_bls12_381_bandersnatch::{EdwardsConfig, Fq, Fr};
+    use ark_ff::{BigInteger, One, PrimeField};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::{vec::Vec, UniformRand};
+    use num_traits::Zero;
+
+    #[test]
+    fn test_windowed_scalar_mul() {
+        let mut rng = ark_std::test_rng();
+        for point in [
+            Projective::<EdwardsConfig>::rand(&mut rng),
+            Projective::zero(),
+        ] {
+            for scalar in [Fr::rand(&mut rng), Fr::zero(), Fr::one(), -Fr::one()] {
+                for window_bits in 1..=4 {
+                    let cs = ConstraintSystem::new_ref();
+                    let point_var =
+                        AffineVar::<EdwardsConfig, FpVar<Fq>>::new_witness(cs.clone(), || {
+                            Ok(point)
+                        })
+                        .unwrap();
+                    let bits = Vec::<Boolean<Fq>>::new_witness(cs.clone(), || {
+                        Ok(scalar.into_bigint().to_bits_le())
+                    })
+                    .unwrap();
+                    let result = point_var
+                        .windowed_scalar_mul_le(bits.iter(), window_bits)
+                        .unwrap();
+                    assert_eq!(result.value().unwrap(), point * scalar);
+                    assert!(cs.is_satisfied().unwrap());
+                }
+            }
+        }
+
+        // Compare the number of constraints with the bitwise method.
+        let cs = ConstraintSystem::new_ref();
+        let point_var = AffineVar::<EdwardsConfig, FpVar<Fq>>::new_witness(cs.clone(), || {
+            Ok(Projective::rand(&mut rng))
+        })
+        .unwrap();
+        let bits = Vec::<Boolean<Fq>>::new_witness(cs.clone(), || {
+            let mut bits = Fr::rand(&mut rng).into_bigint().to_bits_le();
+            bits.truncate(Fr::MODULUS_BIT_SIZE as usize);
+            Ok(bits)
+        })
+        .unwrap();
+        let (bitwise, bitwise_cost) =
+            constraints_added(&cs, || point_var.scalar_mul_le(bits.iter()).unwrap());
+        let (windowed, windowed_cost) = constraints_added(&cs, || {
+            point_var.windowed_scalar_mul_le(bits.iter(), 3).unwrap()
+        });
+        assert!(windowed_cost < bitwise_cost);
+        windowed.enforce_equal(&bitwise).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_multi_scalar_mul() {
+        type EdwardsVar = AffineVar<EdwardsConfig, FpVar<Fq>>;
+        let mut rng = ark_std::test_rng();
+        let p = Projective::<EdwardsConfig>::rand(&mut rng);
+        let bases = [p, Projective::zero(), p, Projective::rand(&mut rng)];
+        let scalars = [
+            Fr::rand(&mut rng),
+            Fr::rand(&mut rng),
+            -Fr::one(),
+            Fr::from(5u64),
+        ];
+
+        let cs = ConstraintSystem::new_ref();
+        let base_vars = bases
+            .iter()
+            .map(|b| EdwardsVar::new_witness(cs.clone(), || Ok(*b)).unwrap())
+            .collect::<Vec<_>>();
+        let mut scalar_vars = scalars
+            .iter()
+            .map(|s| {
+                Vec::<Boolean<Fq>>::new_witness(cs.clone(), || Ok(s.into_bigint().to_bits_le()))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        // Shorter scalars are padded with zeros.
+        scalar_vars[3].truncate(3);
+        let result = EdwardsVar::multi_scalar_mul_le(&base_vars, &scalar_vars).unwrap();
+        let expected: Projective<EdwardsConfig> =
+            bases.iter().zip(&scalars).map(|(b, s)| *b * s).sum();
+        assert_eq!(result.value().unwrap(), expected);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
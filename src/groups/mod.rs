@@ -3,11 +3,12 @@ use crate::{
     fields::emulated_fp::EmulatedFpVar,
     prelude::*,
 };
-use ark_ff::PrimeField;
+use ark_ff::{BitIteratorBE, One, PrimeField};
 use ark_relations::gr1cs::{Namespace, SynthesisError};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
 use ark_ec::CurveGroup;
+use ark_std::vec::Vec;
 use core::{borrow::Borrow, fmt::Debug};
 
 /// This module contains implementations of arithmetic for various curve models.
@@ -79,6 +80,28 @@ pub trait CurveVar<C: CurveGroup, ConstraintF: PrimeField>:
     /// Enforce that `self` is in the prime-order subgroup.
     fn enforce_prime_order(&self) -> Result<(), SynthesisError>;
 
+    /// Returns a `Boolean` representing whether `self` is in the prime-order
+    /// subgroup.
+    ///
+    /// Unlike [`Self::enforce_prime_order`], the constraint system remains
+    /// satisfiable for points outside the subgroup. By default, this
+    /// multiplies by the prime order minus one, and checks that the result is
+    /// `-self`.
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_in_prime_order_subgroup(&self) -> Result<Boolean<ConstraintF>, SynthesisError> {
+        let r_minus_1 = (-C::ScalarField::one()).into_bigint();
+
+        let mut result = Self::zero();
+        for b in BitIteratorBE::without_leading_zeros(r_minus_1) {
+            result.double_in_place()?;
+
+            if b {
+                result += self;
+            }
+        }
+        self.negate()?.is_eq(&result)
+    }
+
     /// Computes `self + self`.
     #[tracing::instrument(target = "gr1cs")]
     fn double(&self) -> Result<Self, SynthesisError> {
@@ -168,4 +191,74 @@ pub trait CurveVar<C: CurveGroup, ConstraintF: PrimeField>:
         }
         Ok(result)
     }
+
+    /// Computes `Σⱼ(scalarⱼ * baseⱼ)` for all j, where `scalarⱼ` is a
+    /// `Boolean` *little-endian* representation of the j-th scalar, and the
+    /// bases are variables.
+    ///
+    /// This interleaves the double-and-add algorithms of all terms (Straus's
+    /// method), so that the doublings are shared among them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bases` and `scalars` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(bases, scalars))]
+    fn multi_scalar_mul_le(
+        bases: &[Self],
+        scalars: &[Vec<Boolean<ConstraintF>>],
+    ) -> Result<Self, SynthesisError> {
+        assert_eq!(bases.len(), scalars.len());
+        let num_bits = scalars.iter().map(Vec::len).max().unwrap_or(0);
+
+        // Computes the standard big-endian double-and-add algorithm for all
+        // terms at once. This relies on the addition formulae being complete.
+        let mut result = Self::zero();
+        for i in (0..num_bits).rev() {
+            result.double_in_place()?;
+            for (base, bits) in bases.iter().zip(scalars) {
+                if let Some(bit) = bits.get(i) {
+                    let result_plus_base = result.clone() + base;
+                    result = bit.select(&result_plus_base, &result)?;
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Recodes the little-endian, non-empty scalar `bits` into windows of
+/// `window_bits` signed odd digits, to be used with
+/// [`TableLookupGadget::cond_neg_lookup`] on the table `[P, 3P, 5P, ...]` of
+/// the first `2^(window_bits - 1)` odd multiples of a point `P`.
+///
+/// The windows are returned most significant first. Together, they represent
+/// `k + 1 - bits[0]`, i.e. the scalar `k` rounded up to an odd integer, and
+/// the most significant window is always positive.
+pub(crate) fn signed_odd_digit_windows<F: PrimeField>(
+    bits: &[Boolean<F>],
+    window_bits: usize,
+) -> Vec<Vec<Boolean<F>>> {
+    assert!(!bits.is_empty() && window_bits > 0);
+    // Any odd `k < 2^n` is equal to `Σᵢ (2cᵢ - 1) * 2^i` for `i < n`, where
+    // `c = (k + 2^n - 1) / 2 = ⌊k / 2⌋ + 2^(n - 1)`. The bits of `c` are thus
+    // those of `k` shifted down by one, with the top bit set, and do not
+    // depend on the lowest bit of `k`.
+    //
+    // A window of `c` with top bit `t` and lower bits `u` represents the digit
+    // `2u + 1` if `t` is set, and `-(2ū + 1)` otherwise, where `ū` is `u` with
+    // all bits flipped.
+    let num_windows = (bits.len() + window_bits - 1) / window_bits;
+    let mut c = bits[1..].to_vec();
+    c.resize(num_windows * window_bits - 1, Boolean::FALSE);
+    c.push(Boolean::TRUE);
+    c.chunks(window_bits)
+        .rev()
+        .map(|window| {
+            let (t, u) = window.split_last().unwrap();
+            let is_negative = !t;
+            let mut digit = u.iter().map(|b| b ^ &is_negative).collect::<Vec<_>>();
+            digit.push(is_negative);
+            digit
+        })
+        .collect()
 }
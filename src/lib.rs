@@ -57,12 +57,16 @@ pub mod eq;
 /// Definitions of polynomial variables over finite fields.
 pub mod poly;
 
+/// Utilities for checking that variables lie in a range of integers.
+pub mod range_check;
+
 /// Contains traits for conditionally selecting a variable from a
 /// list of variables.
 pub mod select;
 
-#[cfg(test)]
-pub(crate) mod test_utils;
+/// Helpers for measuring the cost of gadgets in tests.
+#[doc(hidden)]
+pub mod test_utils;
 
 /// This module contains `UInt8`, a R1CS equivalent of the `u8` type.
 pub mod uint8;
@@ -106,6 +110,7 @@ pub mod prelude {
         fields::{FieldOpsBounds, FieldVar},
         groups::{CurveVar, GroupOpsBounds},
         pairing::PairingVar,
+        range_check::RangeCheckGadget,
         select::*,
         uint128::UInt128,
         uint16::UInt16,
@@ -0,0 +1,128 @@
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+use crate::{boolean::Boolean, fields::fp::FpVar};
+
+/// The strategy used to enforce that a value fits in a given number of bits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RangeCheckStrategy {
+    /// Decompose the value into Boolean witnesses. This costs one constraint
+    /// per bit, plus one to check the recomposition.
+    #[default]
+    Bits,
+    /// Decompose the value into windows of `window_bits` bits, and check that
+    /// every window lies in the table `[0, 2^window_bits)`.
+    ///
+    /// Tables are checked with a single constraint per window when the
+    /// corresponding predicate has been registered via
+    /// [`crate::fields::fp::predicate::register_range`]. If the predicate for
+    /// full windows is missing, the check falls back to
+    /// [`RangeCheckStrategy::Bits`], and a narrower last window without a
+    /// predicate is decomposed into bits.
+    Windowed {
+        /// The number of bits covered by each window.
+        window_bits: usize,
+    },
+}
+
+/// Specifies how to generate constraints that check that `self` lies in a
+/// range of integers.
+pub trait RangeCheckGadget<F: PrimeField>: Sized {
+    /// Enforces that `self`, interpreted as an integer in `[0, p)`, is smaller
+    /// than `2^n`, using `strategy`.
+    ///
+    /// This is trivially true (and generates no constraints) if `n >=
+    /// F::MODULUS_BIT_SIZE`.
+    fn enforce_bit_length_with_strategy(
+        &self,
+        n: usize,
+        strategy: RangeCheckStrategy,
+    ) -> Result<(), SynthesisError>;
+
+    /// Enforces that `self`, interpreted as an integer in `[0, p)`, is smaller
+    /// than `2^n`, using the default [`RangeCheckStrategy::Bits`].
+    fn enforce_bit_length(&self, n: usize) -> Result<(), SynthesisError> {
+        self.enforce_bit_length_with_strategy(n, RangeCheckStrategy::default())
+    }
+
+    /// Enforces that `self` is smaller than `2^n`, and outputs the `n`-bit
+    /// little-endian decomposition of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n >= F::MODULUS_BIT_SIZE`.
+    fn to_bits_le_with_bit_length(&self, n: usize) -> Result<Vec<Boolean<F>>, SynthesisError>;
+
+    /// Outputs a `Boolean` representing whether `lo <= self < hi`, where all
+    /// three are interpreted as integers in `[0, p)`.
+    ///
+    /// The constraint system is satisfiable regardless of the value of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi`.
+    fn is_in_range(&self, lo: F, hi: F) -> Result<Boolean<F>, SynthesisError>;
+}
+
+/// Collects range checks during synthesis, and enforces them in one go at the
+/// end of a [`RangeCheckBatch::scope`].
+///
+/// Deferring the checks allows repeated checks on the same variable, which
+/// are common when the same limb or carry flows through several gadgets, to
+/// be merged into a single check against the tightest bound.
+#[derive(Debug)]
+pub struct RangeCheckBatch<F: PrimeField> {
+    strategy: RangeCheckStrategy,
+    checks: Vec<(FpVar<F>, usize)>,
+}
+
+impl<F: PrimeField> RangeCheckBatch<F> {
+    /// Runs `f` with an empty batch, and then enforces all checks that `f`
+    /// recorded in it using `strategy`.
+    #[tracing::instrument(target = "gr1cs", skip(f))]
+    pub fn scope<T>(
+        strategy: RangeCheckStrategy,
+        f: impl FnOnce(&mut Self) -> Result<T, SynthesisError>,
+    ) -> Result<T, SynthesisError> {
+        let mut batch = Self {
+            strategy,
+            checks: Vec::new(),
+        };
+        let output = f(&mut batch)?;
+        for (value, n) in batch.checks {
+            value.enforce_bit_length_with_strategy(n, strategy)?;
+        }
+        Ok(output)
+    }
+
+    /// Returns the number of distinct checks that are pending.
+    pub fn len(&self) -> usize {
+        self.checks.len()
+    }
+
+    /// Returns `true` if no checks are pending.
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Records that `value` should be smaller than `2^n`.
+    ///
+    /// Constants are checked immediately.
+    pub fn enforce_bit_length(&mut self, value: &FpVar<F>, n: usize) -> Result<(), SynthesisError> {
+        match value {
+            FpVar::Constant(_) => value.enforce_bit_length(n),
+            FpVar::Var(v) => {
+                let existing = self.checks.iter_mut().find(|(other, _)| match other {
+                    FpVar::Var(other) => other.variable == v.variable,
+                    FpVar::Constant(_) => false,
+                });
+                match existing {
+                    Some((_, bound)) => *bound = (*bound).min(n),
+                    None => self.checks.push((value.clone(), n)),
+                }
+                Ok(())
+            },
+        }
+    }
+}
@@ -28,39 +28,52 @@ pub trait CondSelectGadget<ConstraintF: Field>: Sized + Clone {
         position: &[Boolean<ConstraintF>],
         values: &[Self],
     ) -> Result<Self, SynthesisError> {
-        let m = values.len();
-        let n = position.len();
+        select_power_of_two_tree(position, values)
+    }
+}
 
-        // Assert m is a power of 2, and n = log(m)
-        assert!(m.is_power_of_two());
-        assert_eq!(1 << n, m);
+/// Selects the element of `values` whose index is represented by the
+/// big-endian bits in `position`, using a tree of `values.len() - 1`
+/// conditional selections.
+///
+/// This is the default implementation of
+/// [`CondSelectGadget::conditionally_select_power_of_two_vector`].
+pub(crate) fn select_power_of_two_tree<ConstraintF: Field, T: CondSelectGadget<ConstraintF>>(
+    position: &[Boolean<ConstraintF>],
+    values: &[T],
+) -> Result<T, SynthesisError> {
+    let m = values.len();
+    let n = position.len();
 
-        let mut cur_mux_values = values.to_vec();
+    // Assert m is a power of 2, and n = log(m)
+    assert!(m.is_power_of_two());
+    assert_eq!(1 << n, m);
 
-        // Traverse the evaluation tree from bottom to top in level order traversal.
-        // This is method 5.1 from https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf
-        // TODO: Add method 5.2/5.3
-        for i in 0..n {
-            // Size of current layer.
-            let cur_size = 1 << (n - i);
-            assert_eq!(cur_mux_values.len(), cur_size);
+    let mut cur_mux_values = values.to_vec();
 
-            let mut next_mux_values = Vec::new();
-            for j in (0..cur_size).step_by(2) {
-                let cur = Self::conditionally_select(
-                    &position[n - 1 - i],
-                    // true case
-                    &cur_mux_values[j + 1],
-                    // false case
-                    &cur_mux_values[j],
-                )?;
-                next_mux_values.push(cur);
-            }
-            cur_mux_values = next_mux_values;
-        }
+    // Traverse the evaluation tree from bottom to top in level order traversal.
+    // This is method 5.1 from https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf
+    // TODO: Add method 5.2/5.3
+    for i in 0..n {
+        // Size of current layer.
+        let cur_size = 1 << (n - i);
+        assert_eq!(cur_mux_values.len(), cur_size);
 
-        Ok(cur_mux_values[0].clone())
+        let mut next_mux_values = Vec::new();
+        for j in (0..cur_size).step_by(2) {
+            let cur = T::conditionally_select(
+                &position[n - 1 - i],
+                // true case
+                &cur_mux_values[j + 1],
+                // false case
+                &cur_mux_values[j],
+            )?;
+            next_mux_values.push(cur);
+        }
+        cur_mux_values = next_mux_values;
     }
+
+    Ok(cur_mux_values[0].clone())
 }
 
 /// Performs a lookup in a 4-element table using two bits.
@@ -107,3 +120,43 @@ pub trait ThreeBitCondNegLookupGadget<ConstraintF: Field>: Sized {
         constants: &[Self::TableConstant],
     ) -> Result<Self, SynthesisError>;
 }
+
+/// Performs lookups into tables of variables.
+///
+/// This generalises [`TwoBitLookupGadget`] and [`ThreeBitCondNegLookupGadget`]
+/// to tables that are computed in-circuit, such as the multiples of a
+/// variable curve point, and to any number of bits.
+pub trait TableLookupGadget<ConstraintF: Field>: CondSelectGadget<ConstraintF> {
+    /// Outputs `-self` if `cond == &Boolean::TRUE`, and `self` otherwise.
+    fn conditionally_negate(&self, cond: &Boolean<ConstraintF>) -> Result<Self, SynthesisError>;
+
+    /// Interprets the slice `bits` as a little-endian integer `b`, and then
+    /// outputs `table[b]`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `table.len() != 1 << bits.len()`.
+    fn lookup(bits: &[Boolean<ConstraintF>], table: &[Self]) -> Result<Self, SynthesisError> {
+        let position = bits.iter().rev().cloned().collect::<Vec<_>>();
+        Self::conditionally_select_power_of_two_vector(&position, table)
+    }
+
+    /// Interprets all but the last element of `bits` as a little-endian
+    /// integer `b`, and then outputs `table[b] * c`, where `c = if
+    /// bits.last() { -1 } else { 1 }`.
+    ///
+    /// For example, if `bits == [1, 0, 1]`, and `table == [0, 1, 2, 3]`, this
+    /// method should output a variable corresponding to `-1`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `bits` is empty or if `table.len() != 1 <<
+    /// (bits.len() - 1)`.
+    fn cond_neg_lookup(
+        bits: &[Boolean<ConstraintF>],
+        table: &[Self],
+    ) -> Result<Self, SynthesisError> {
+        let (is_negative, bits) = bits.split_last().expect("`bits` must not be empty");
+        Self::lookup(bits, table)?.conditionally_negate(is_negative)
+    }
+}
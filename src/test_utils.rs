@@ -1,15 +1,115 @@
+#[cfg(test)]
 use core::iter;
 
+use ark_ff::PrimeField;
+#[cfg(test)]
+use ark_relations::gr1cs::OptimizationGoal;
+use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, R1CS_PREDICATE_LABEL};
+
+#[cfg(test)]
 use crate::alloc::AllocationMode;
 
+#[cfg(test)]
 pub(crate) fn modes() -> impl Iterator<Item = AllocationMode> {
     use AllocationMode::*;
     [Constant, Input, Witness].into_iter()
 }
 
+#[cfg(test)]
 pub(crate) fn combination<T: Clone>(
     mut i: impl Iterator<Item = T>,
 ) -> impl Iterator<Item = (AllocationMode, T)> {
     iter::from_fn(move || i.next().map(|t| modes().map(move |mode| (mode, t.clone()))))
         .flat_map(|x| x)
 }
+
+/// The size of a constraint system, or of the part of it added by a gadget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cost {
+    /// The number of constraints.
+    pub constraints: usize,
+    /// The number of witness variables.
+    pub witness_variables: usize,
+    /// The number of nonzero entries in the R1CS matrices after finalization.
+    pub nonzeros: usize,
+}
+
+/// Returns a finalized copy of `cs`, leaving `cs` itself untouched.
+fn finalized<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> Option<ConstraintSystem<F>> {
+    match cs {
+        ConstraintSystemRef::None => None,
+        ConstraintSystemRef::CS(r) => {
+            let mut cs = r.borrow().clone();
+            cs.finalize();
+            Some(cs)
+        },
+    }
+}
+
+/// Returns the number of nonzero entries in the R1CS matrices of `cs` once it
+/// is finalized.
+pub fn num_nonzeros<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> usize {
+    finalized(cs).map_or(0, |cs| {
+        match cs.to_matrices().unwrap().get(R1CS_PREDICATE_LABEL) {
+            None => 0,
+            Some(matrices) => matrices
+                .iter()
+                .flat_map(|matrix| matrix.iter().map(|row| row.len()))
+                .sum(),
+        }
+    })
+}
+
+/// Returns the current size of `cs`.
+pub fn cost_of<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> Cost {
+    Cost {
+        constraints: cs.num_constraints(),
+        witness_variables: cs.num_witness_variables(),
+        nonzeros: num_nonzeros(cs),
+    }
+}
+
+/// Runs `f`, and returns its output together with the number of constraints
+/// that it adds to `cs`.
+pub fn constraints_added<F: PrimeField, T>(
+    cs: &ConstraintSystemRef<F>,
+    f: impl FnOnce() -> T,
+) -> (T, usize) {
+    let before = cs.num_constraints();
+    let output = f();
+    (output, cs.num_constraints() - before)
+}
+
+/// Runs `f`, and returns its output together with the cost that it adds to
+/// `cs`. Unlike [`constraints_added`], this finalizes copies of `cs` to count
+/// the nonzero entries.
+pub fn cost_added<F: PrimeField, T>(
+    cs: &ConstraintSystemRef<F>,
+    f: impl FnOnce() -> T,
+) -> (T, Cost) {
+    let before = cost_of(cs);
+    let output = f();
+    let after = cost_of(cs);
+    let cost = Cost {
+        constraints: after.constraints - before.constraints,
+        witness_variables: after.witness_variables - before.witness_variables,
+        nonzeros: after.nonzeros - before.nonzeros,
+    };
+    (output, cost)
+}
+
+/// Runs `circuit` on a fresh constraint system whose optimization goal is
+/// `goal`, and returns the number of constraints and the number of nonzero
+/// entries in the R1CS matrices of the finalized system.
+#[cfg(test)]
+pub(crate) fn cost_with_goal<F: PrimeField>(
+    goal: OptimizationGoal,
+    circuit: impl FnOnce(ConstraintSystemRef<F>),
+) -> (usize, usize) {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_optimization_goal(goal);
+    circuit(cs.clone());
+    assert!(cs.is_satisfied().unwrap());
+    let num_constraints = finalized(&cs).unwrap().num_constraints();
+    (num_constraints, num_nonzeros(&cs))
+}
@@ -22,6 +22,10 @@ impl<const N: usize, T: PrimUInt, F: PrimeField> UInt<N, T, F> {
     /// wrapping_add(operands[2])...`.
     ///
     /// The user must ensure that overflow does not occur.
+    ///
+    /// The cost does not depend on the optimization goal: the sum is
+    /// decomposed into booleans, and the operands' bits are written out once,
+    /// in the single constraint that recomposes the sum.
     #[tracing::instrument(target = "gr1cs", skip(operands))]
     pub fn wrapping_add_many(operands: &[Self]) -> Result<Self, SynthesisError>
     where
@@ -48,10 +52,12 @@ mod tests {
     use crate::{
         alloc::{AllocVar, AllocationMode},
         prelude::EqGadget,
+        test_utils::cost_with_goal,
         uint::test_utils::{run_binary_exhaustive, run_binary_random},
         GR1CSVar,
     };
     use ark_ff::PrimeField;
+    use ark_relations::gr1cs::{ConstraintSystemRef, OptimizationGoal};
     use ark_test_curves::bls12_381::Fr;
 
     fn uint_wrapping_add<T: PrimUInt, const N: usize, F: PrimeField>(
@@ -79,6 +85,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn wrapping_add_many_optimization_goal() {
+        let circuit = |cs: ConstraintSystemRef<Fr>| {
+            let operands = (0..4u32)
+                .map(|i| UInt::<32, u32, Fr>::new_witness(cs.clone(), || Ok(i * 1000)).unwrap())
+                .collect::<Vec<_>>();
+            let sum = UInt::wrapping_add_many(&operands).unwrap();
+            assert_eq!(sum.value().unwrap(), 6000);
+        };
+        assert_eq!(
+            cost_with_goal(OptimizationGoal::Constraints, circuit),
+            cost_with_goal(OptimizationGoal::Weight, circuit),
+        );
+    }
+
     #[test]
     fn u8_wrapping_add() {
         run_binary_exhaustive(uint_wrapping_add::<u8, 8, Fr>).unwrap()
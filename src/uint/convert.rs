@@ -1,4 +1,4 @@
-use crate::{convert::*, fields::fp::FpVar};
+use crate::{convert::*, fields::fp::FpVar, range_check::RangeCheckGadget};
 
 use super::*;
 
@@ -22,6 +22,9 @@ impl<const N: usize, F: Field, T: PrimUInt> UInt<N, T, F> {
     /// Converts a field element into its little-endian bit order
     /// representation.
     ///
+    /// Also returns the remainder `other - sum_i 2^i * bits[i]`, which is
+    /// enforced to be zero.
+    ///
     /// # Panics
     ///
     /// Assumes that `N` is at most the number of bits in `F::MODULUS_BIT_SIZE -
@@ -30,7 +33,8 @@ impl<const N: usize, F: Field, T: PrimUInt> UInt<N, T, F> {
     where
         F: PrimeField,
     {
-        let (bits, rest) = other.to_bits_le_with_top_bits_zero(N)?;
+        let bits = other.to_bits_le_with_bit_length(N)?;
+        let rest = other - Boolean::le_bits_to_fp(&bits)?;
         let result = Self::from_bits_le(&bits);
         Ok((result, rest))
     }
@@ -14,6 +14,7 @@ mod not;
 mod or;
 mod rotate;
 mod select;
+mod set;
 mod shl;
 mod shr;
 mod xor;
@@ -0,0 +1,110 @@
+use super::*;
+
+impl<const N: usize, T: PrimUInt, F: PrimeField> UInt<N, T, F> {
+    /// Enforces that `self` is equal to one of the elements of `set`.
+    ///
+    /// See [`crate::fields::fp::FpVar::enforce_in_set`] for the cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_in_set(&self, set: &[T]) -> Result<(), SynthesisError> {
+        self.to_fp()?.enforce_in_set(&Self::set_to_fp(set))
+    }
+
+    /// Outputs a `Boolean` representing whether `self` is equal to one of the
+    /// elements of `set`.
+    ///
+    /// See [`crate::fields::fp::FpVar::is_in_set`] for the cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_in_set(&self, set: &[T]) -> Result<Boolean<F>, SynthesisError> {
+        self.to_fp()?.is_in_set(&Self::set_to_fp(set))
+    }
+
+    /// Enforces that `self` is equal to one of the elements of `set`, and
+    /// outputs the one-hot encoding of its position.
+    ///
+    /// See [`crate::fields::fp::FpVar::index_in_set`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N >= F::MODULUS_BIT_SIZE`, or if `set` contains duplicates.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn index_in_set(&self, set: &[T]) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        self.to_fp()?.index_in_set(&Self::set_to_fp(set))
+    }
+
+    fn set_to_fp(set: &[T]) -> Vec<F> {
+        set.iter().map(|s| F::from((*s).into())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        uint::test_utils::{run_unary_exhaustive, run_unary_random},
+        GR1CSVar,
+    };
+    use ark_test_curves::bls12_381::Fr;
+
+    fn uint_in_set<T: PrimUInt, const N: usize, F: PrimeField>(
+        a: UInt<N, T, F>,
+    ) -> Result<(), SynthesisError> {
+        let cs = a.cs();
+        let value = a.value()?;
+        // A set that does not contain `value`, and one that does.
+        let without = [T::zero(), T::from(42u8).unwrap(), T::max_value()]
+            .into_iter()
+            .filter(|s| *s != value)
+            .collect::<Vec<_>>();
+        let mut with = without.clone();
+        with.insert(1, value);
+
+        assert!(a.is_in_set(&with)?.value()?);
+        assert!(!a.is_in_set(&without)?.value()?);
+        let index = a.index_in_set(&with)?;
+        for (bit, s) in index.iter().zip(&with) {
+            assert_eq!(bit.value()?, *s == value);
+        }
+        a.enforce_in_set(&with)?;
+        if a.is_constant() {
+            assert!(a.enforce_in_set(&without).is_err());
+        } else {
+            assert!(cs.is_satisfied().unwrap());
+            a.enforce_in_set(&without)?;
+            assert!(!cs.is_satisfied().unwrap());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn u8_in_set() {
+        run_unary_exhaustive(uint_in_set::<u8, 8, Fr>).unwrap()
+    }
+
+    #[test]
+    fn u16_in_set() {
+        run_unary_random::<1000, 16, _, _>(uint_in_set::<u16, 16, Fr>).unwrap()
+    }
+
+    #[test]
+    fn u32_in_set() {
+        run_unary_random::<1000, 32, _, _>(uint_in_set::<u32, 32, Fr>).unwrap()
+    }
+
+    #[test]
+    fn u64_in_set() {
+        run_unary_random::<1000, 64, _, _>(uint_in_set::<u64, 64, Fr>).unwrap()
+    }
+
+    #[test]
+    fn u128_in_set() {
+        run_unary_random::<1000, 128, _, _>(uint_in_set::<u128, 128, Fr>).unwrap()
+    }
+}
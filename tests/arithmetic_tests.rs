@@ -1,22 +1,38 @@
 use ark_bls12_381::Bls12_381;
 use ark_ec::{pairing::Pairing, CurveGroup};
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::{
+    fields::{Fp64, MontBackend, MontConfig},
+    BigInteger, Field, PrimeField,
+};
 use ark_mnt4_298::MNT4_298;
 use ark_mnt4_753::MNT4_753;
 use ark_mnt6_298::MNT6_298;
 use ark_mnt6_753::MNT6_753;
 
 use ark_r1cs_std::{
-    alloc::AllocVar,
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    cmp::CmpGadget,
+    convert::ToBitsGadget,
     eq::EqGadget,
     fields::{
-        emulated_fp::{AllocatedEmulatedFpVar, EmulatedFpVar},
+        emulated_fp::{
+            params::{estimate_costs, estimate_costs_with_params},
+            params_for_goal, to_packed_public_input, to_packed_public_inputs, to_public_input,
+            to_public_inputs, AllocatedEmulatedFpVar, EmulatedFpVar, NonNativeFieldConfig,
+            PackedEmulatedFpVar,
+        },
+        fp::FpVar,
+        fp12::EmulatedFp12Var,
+        fp2::EmulatedFp2Var,
+        fp6_3over2::EmulatedFp6Var,
         FieldVar,
     },
+    test_utils::{constraints_added, cost_added, Cost},
     GR1CSVar,
 };
-use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef};
-use ark_std::rand::RngCore;
+use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, OptimizationGoal};
+use ark_std::{marker::PhantomData, rand::RngCore, One, UniformRand, Zero};
 
 #[cfg(not(ci))]
 const NUM_REPETITIONS: usize = 100;
@@ -28,6 +44,11 @@ const TEST_COUNT: usize = 100;
 #[cfg(ci)]
 const TEST_COUNT: usize = 1;
 
+#[cfg(not(ci))]
+const EXTENSION_REPETITIONS: usize = 10;
+#[cfg(ci)]
+const EXTENSION_REPETITIONS: usize = 1;
+
 fn allocation_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
     cs: ConstraintSystemRef<BaseField>,
     rng: &mut R,
@@ -578,6 +599,148 @@ fn inverse_stress_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
     }
 }
 
+fn batch_inverse_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
+    cs: ConstraintSystemRef<BaseField>,
+    rng: &mut R,
+) {
+    let mut nums_native = (0..TEST_COUNT)
+        .map(|_| TargetF::rand(rng))
+        .collect::<Vec<_>>();
+    nums_native.push(TargetF::zero());
+    let nums = nums_native
+        .iter()
+        .map(|num_native| {
+            EmulatedFpVar::<TargetF, BaseField>::new_witness(ark_relations::ns!(cs, "num"), || {
+                Ok(num_native)
+            })
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let inverses = EmulatedFpVar::batch_inverse(&nums).unwrap();
+    for (inverse, num_native) in inverses.iter().zip(&nums_native) {
+        let expected = num_native.inverse().unwrap_or(TargetF::zero());
+        assert!(inverse.value().unwrap().eq(&expected));
+    }
+
+    let nonzero = &nums[..nums.len() - 1];
+    let inverses = EmulatedFpVar::batch_inverse_unchecked(nonzero).unwrap();
+    for (inverse, num_native) in inverses.iter().zip(&nums_native) {
+        assert!(inverse.value().unwrap().eq(&num_native.inverse().unwrap()));
+    }
+}
+
+fn sqrt_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
+    cs: ConstraintSystemRef<BaseField>,
+    rng: &mut R,
+) {
+    for _ in 0..TEST_COUNT {
+        let num_native = TargetF::rand(rng);
+        let num =
+            EmulatedFpVar::<TargetF, BaseField>::new_witness(ark_relations::ns!(cs, "num"), || {
+                Ok(num_native)
+            })
+            .unwrap();
+
+        let is_square_native = !num_native.legendre().is_qnr();
+        assert_eq!(num.is_square().unwrap().value().unwrap(), is_square_native);
+
+        let legendre_native = if num_native.is_zero() {
+            BaseField::zero()
+        } else if is_square_native {
+            BaseField::one()
+        } else {
+            -BaseField::one()
+        };
+        assert_eq!(num.legendre().unwrap().value().unwrap(), legendre_native);
+
+        if is_square_native {
+            let root = num.sqrt().unwrap();
+            assert_eq!(root.value().unwrap().square(), num_native);
+        }
+    }
+}
+
+fn pow_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
+    cs: ConstraintSystemRef<BaseField>,
+    rng: &mut R,
+) {
+    let base_native = TargetF::rand(rng);
+    let base =
+        EmulatedFpVar::<TargetF, BaseField>::new_witness(ark_relations::ns!(cs, "base"), || {
+            Ok(base_native)
+        })
+        .unwrap();
+    let exp_native = rng.next_u32() as u64;
+    let exp = FpVar::new_witness(ark_relations::ns!(cs, "exp"), || {
+        Ok(BaseField::from(exp_native))
+    })
+    .unwrap();
+    let (result, bitwise_cost) =
+        constraints_added(&cs, || base.pow_with_bit_bound(&exp, 32, 1).unwrap());
+    assert!(result.value().unwrap().eq(&base_native.pow([exp_native])));
+
+    // Emulated multiplications are much more expensive than selections, so
+    // halving their number pays off.
+    let (result, windowed_cost) =
+        constraints_added(&cs, || base.pow_with_bit_bound(&exp, 32, 2).unwrap());
+    assert!(result.value().unwrap().eq(&base_native.pow([exp_native])));
+    assert!(windowed_cost < bitwise_cost);
+}
+
+fn sum_of_products_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
+    cs: ConstraintSystemRef<BaseField>,
+    rng: &mut R,
+) {
+    let mut terms = Vec::new();
+    let mut signed_terms = Vec::new();
+    let mut expected = TargetF::zero();
+    let mut signed_expected = TargetF::zero();
+    for i in 0..8 {
+        let a_native = TargetF::rand(rng);
+        let b_native = TargetF::rand(rng);
+        let a = EmulatedFpVar::<TargetF, BaseField>::new_witness(
+            ark_relations::ns!(cs, "alloc a"),
+            || Ok(a_native),
+        )
+        .unwrap();
+        // Mix in constants and unreduced sums.
+        let b = match i % 3 {
+            0 => EmulatedFpVar::Constant(b_native),
+            1 => EmulatedFpVar::new_witness(ark_relations::ns!(cs, "alloc b"), || Ok(b_native))
+                .unwrap(),
+            _ => &a + &EmulatedFpVar::Constant(b_native - a_native),
+        };
+        let is_negated = i % 2 == 1;
+
+        expected += a_native * b_native;
+        if is_negated {
+            signed_expected -= a_native * b_native;
+        } else {
+            signed_expected += a_native * b_native;
+        }
+        terms.push((a.clone(), b.clone()));
+        signed_terms.push((a, b, is_negated));
+    }
+    terms.push((
+        EmulatedFpVar::Constant(TargetF::one()),
+        EmulatedFpVar::Constant(TargetF::one()),
+    ));
+    expected += TargetF::one();
+
+    let sum = EmulatedFpVar::sum_of_products(&terms).unwrap();
+    assert_eq!(sum.value().unwrap(), expected);
+    let signed_sum = EmulatedFpVar::signed_sum_of_products(&signed_terms).unwrap();
+    assert_eq!(signed_sum.value().unwrap(), signed_expected);
+    assert_eq!(
+        EmulatedFpVar::<TargetF, BaseField>::sum_of_products(&[])
+            .unwrap()
+            .value()
+            .unwrap(),
+        TargetF::zero()
+    );
+}
+
 macro_rules! nonnative_test_individual {
     ($test_method:ident, $test_name:ident, $test_target_field:ty, $test_base_field:ty) => {
         paste::item! {
@@ -693,6 +856,20 @@ macro_rules! nonnative_test {
             $test_target_field,
             $test_base_field
         );
+        nonnative_test_individual!(
+            batch_inverse_test,
+            $test_name,
+            $test_target_field,
+            $test_base_field
+        );
+        nonnative_test_individual!(
+            sum_of_products_test,
+            $test_name,
+            $test_target_field,
+            $test_base_field
+        );
+        nonnative_test_individual!(sqrt_test, $test_name, $test_target_field, $test_base_field);
+        nonnative_test_individual!(pow_test, $test_name, $test_target_field, $test_base_field);
     };
 }
 
@@ -744,3 +921,750 @@ nonnative_test!(
 );
 nonnative_test!(PallasFqFr, ark_pallas::Fq, ark_pallas::Fr);
 nonnative_test!(PallasFrFq, ark_pallas::Fr, ark_pallas::Fq);
+
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+pub type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct BabyBearConfig;
+pub type BabyBear = Fp64<MontBackend<BabyBearConfig, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "2147483647"]
+#[generator = "7"]
+pub struct Mersenne31Config;
+pub type Mersenne31 = Fp64<MontBackend<Mersenne31Config, 1>>;
+
+nonnative_test!(Bn254FrGoldilocks, ark_bn254::Fr, Goldilocks);
+nonnative_test!(Bn254FrBabyBear, ark_bn254::Fr, BabyBear);
+nonnative_test!(Bn254FrMersenne31, ark_bn254::Fr, Mersenne31);
+nonnative_test!(GoldilocksBn254Fr, Goldilocks, ark_bn254::Fr);
+nonnative_test!(BabyBearBn254Fr, BabyBear, ark_bn254::Fr);
+
+fn extension_arithmetic_test<
+    F: Field,
+    V: FieldVar<F, BaseField>,
+    BaseField: PrimeField,
+    R: RngCore,
+>(
+    cs: ConstraintSystemRef<BaseField>,
+    rng: &mut R,
+) {
+    let a_native = F::rand(rng);
+    let b_native = F::rand(rng);
+    let a = V::new_witness(ark_relations::ns!(cs, "alloc a"), || Ok(a_native)).unwrap();
+    let b = V::new_witness(ark_relations::ns!(cs, "alloc b"), || Ok(b_native)).unwrap();
+
+    assert_eq!((a.clone() + &b).value().unwrap(), a_native + b_native);
+    assert_eq!((a.clone() - &b).value().unwrap(), a_native - b_native);
+    assert_eq!((a.clone() * &b).value().unwrap(), a_native * b_native);
+    assert_eq!((a.clone() * b_native).value().unwrap(), a_native * b_native);
+    assert_eq!(a.double().unwrap().value().unwrap(), a_native.double());
+    assert_eq!(a.negate().unwrap().value().unwrap(), -a_native);
+    assert_eq!(a.square().unwrap().value().unwrap(), a_native.square());
+    assert_eq!(
+        a.inverse().unwrap().value().unwrap(),
+        a_native.inverse().unwrap()
+    );
+    for power in 0..3 {
+        assert_eq!(
+            a.frobenius_map(power).unwrap().value().unwrap(),
+            a_native.frobenius_map(power)
+        );
+    }
+
+    let a_times_b = V::new_witness(ark_relations::ns!(cs, "alloc a * b"), || {
+        Ok(a_native * b_native)
+    })
+    .unwrap();
+    a.mul_equals(&b, &a_times_b).unwrap();
+    (a.clone() * &b).enforce_equal(&a_times_b).unwrap();
+    assert!(a.is_eq(&b).unwrap().value().unwrap() == (a_native == b_native));
+}
+
+macro_rules! emulated_extension_test {
+    ($test_name:ident, $test_extension_field:ty, $test_extension_var:ty, $test_base_field:ty) => {
+        #[test]
+        fn $test_name() {
+            let rng = &mut ark_std::test_rng();
+
+            for _ in 0..EXTENSION_REPETITIONS {
+                let cs = ConstraintSystem::<$test_base_field>::new_ref();
+                extension_arithmetic_test::<$test_extension_field, $test_extension_var, _, _>(
+                    cs.clone(),
+                    rng,
+                );
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    };
+}
+
+emulated_extension_test!(
+    emulated_fp2_bls12_bn254,
+    ark_bls12_381::Fq2,
+    EmulatedFp2Var<ark_bls12_381::Fq2Config, ark_bn254::Fr>,
+    ark_bn254::Fr
+);
+emulated_extension_test!(
+    emulated_fp6_bls12_bn254,
+    ark_bls12_381::Fq6,
+    EmulatedFp6Var<ark_bls12_381::Fq6Config, ark_bn254::Fr>,
+    ark_bn254::Fr
+);
+emulated_extension_test!(
+    emulated_fp12_bls12_bn254,
+    ark_bls12_381::Fq12,
+    EmulatedFp12Var<ark_bls12_381::Fq12Config, ark_bn254::Fr>,
+    ark_bn254::Fr
+);
+
+const CUSTOM_LAYOUT: NonNativeFieldConfig = NonNativeFieldConfig {
+    num_limbs: 6,
+    bits_per_limb: 64,
+};
+
+#[test]
+fn emulated_custom_layout_test() {
+    type TargetF = ark_bls12_381::Fq;
+    type BaseF = ark_bls12_381::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let cs = ConstraintSystem::<BaseF>::new_ref();
+    assert!(CUSTOM_LAYOUT.is_valid_for::<TargetF, BaseF>());
+    assert_ne!(
+        CUSTOM_LAYOUT,
+        AllocatedEmulatedFpVar::<TargetF, BaseF>::default_params(&cs)
+    );
+
+    let a_native = TargetF::rand(rng);
+    let b_native = TargetF::rand(rng);
+    let c_native = TargetF::rand(rng);
+    let alloc = |v: TargetF| {
+        EmulatedFpVar::<TargetF, BaseF>::new_variable_with_params(
+            cs.clone(),
+            || Ok(v),
+            AllocationMode::Witness,
+            CUSTOM_LAYOUT,
+        )
+        .unwrap()
+    };
+    let a = alloc(a_native);
+    let b = alloc(b_native);
+    let c = alloc(c_native);
+    assert_eq!(a.params(), Some(CUSTOM_LAYOUT));
+    match &a {
+        EmulatedFpVar::Var(v) => assert_eq!(v.limbs.len(), CUSTOM_LAYOUT.num_limbs),
+        EmulatedFpVar::Constant(_) => panic!("expected a variable"),
+    }
+
+    let expected = (a_native + b_native) * c_native - a_native.square();
+    let result = (&a + &b) * &c - a.square().unwrap();
+    assert_eq!(result.value().unwrap(), expected);
+    assert_eq!(result.params(), Some(CUSTOM_LAYOUT));
+
+    // Constants adapt to the layout of the variable.
+    let d = &result * b_native + c_native;
+    assert_eq!(d.value().unwrap(), expected * b_native + c_native);
+    d.enforce_equal(&EmulatedFpVar::Constant(expected * b_native + c_native))
+        .unwrap();
+
+    let inv = a.inverse().unwrap();
+    assert_eq!(inv.value().unwrap(), a_native.inverse().unwrap());
+    let sum = (a.mul_without_reduce(&b).unwrap() + c.mul_without_reduce(&c).unwrap())
+        .reduce()
+        .unwrap();
+    assert_eq!(
+        sum.value().unwrap(),
+        a_native * b_native + c_native.square()
+    );
+
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "different limb layouts")]
+fn emulated_mixed_layout_test() {
+    type TargetF = ark_bls12_381::Fq;
+    type BaseF = ark_bls12_381::Fr;
+
+    let cs = ConstraintSystem::<BaseF>::new_ref();
+    let a = EmulatedFpVar::<TargetF, BaseF>::new_variable_with_params(
+        cs.clone(),
+        || Ok(TargetF::from(3u8)),
+        AllocationMode::Witness,
+        CUSTOM_LAYOUT,
+    )
+    .unwrap();
+    let b = EmulatedFpVar::<TargetF, BaseF>::new_witness(cs, || Ok(TargetF::from(5u8))).unwrap();
+    let _ = &a * &b;
+}
+
+#[test]
+fn emulated_public_input_test() {
+    type TargetF = ark_bls12_381::Fq;
+    type BaseF = ark_bn254::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let values: Vec<TargetF> = (0..3).map(|_| TargetF::rand(rng)).collect();
+
+    for goal in [OptimizationGoal::Constraints, OptimizationGoal::Weight] {
+        let cs = ConstraintSystem::<BaseF>::new_ref();
+        cs.set_optimization_goal(goal);
+        let params = params_for_goal::<TargetF, BaseF>(goal);
+        let expected = to_public_inputs::<TargetF, BaseF>(&values, &params);
+        assert_eq!(expected.len(), values.len() * params.num_limbs);
+
+        let num_inputs = cs.num_instance_variables();
+        let vars =
+            Vec::<EmulatedFpVar<TargetF, BaseF>>::new_input(cs.clone(), || Ok(values.clone()))
+                .unwrap();
+        assert_eq!(cs.num_instance_variables() - num_inputs, expected.len());
+
+        let mut actual = Vec::new();
+        for (var, value) in vars.iter().zip(&values) {
+            let limbs: Vec<BaseF> = match var {
+                EmulatedFpVar::Var(v) => v.limbs.iter().map(|l| l.value().unwrap()).collect(),
+                EmulatedFpVar::Constant(_) => panic!("expected a variable"),
+            };
+            assert_eq!(limbs, to_public_input::<TargetF, BaseF>(value, &params));
+            assert_eq!(
+                AllocatedEmulatedFpVar::<TargetF, BaseF>::limbs_to_value_with_params(
+                    limbs.clone(),
+                    &params
+                ),
+                *value
+            );
+            actual.extend(limbs);
+        }
+        assert_eq!(actual, expected);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
+
+#[test]
+fn emulated_packed_input_test() {
+    type TargetF = ark_bls12_381::Fq;
+    type BaseF = ark_bn254::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let capacity = BaseF::MODULUS_BIT_SIZE as usize - 1;
+    let expected_len = (TargetF::MODULUS_BIT_SIZE as usize + capacity - 1) / capacity;
+
+    for goal in [OptimizationGoal::Constraints, OptimizationGoal::Weight] {
+        let cs = ConstraintSystem::<BaseF>::new_ref();
+        cs.set_optimization_goal(goal);
+
+        let a_native = TargetF::rand(rng);
+        let b_native = TargetF::rand(rng);
+        let packed = to_packed_public_input::<TargetF, BaseF>(&a_native);
+        assert_eq!(packed.len(), expected_len);
+        assert!(expected_len < params_for_goal::<TargetF, BaseF>(goal).num_limbs);
+
+        // The packed inputs are the little-endian chunks of the bits of `a`.
+        let mut bits = Vec::new();
+        for p in &packed {
+            bits.extend(p.into_bigint().to_bits_le().into_iter().take(capacity));
+        }
+        bits.truncate(TargetF::MODULUS_BIT_SIZE as usize);
+        assert_eq!(
+            TargetF::from_bigint(<TargetF as PrimeField>::BigInt::from_bits_le(&bits)).unwrap(),
+            a_native
+        );
+        assert_eq!(
+            to_packed_public_inputs::<TargetF, BaseF>(&[a_native, b_native]).len(),
+            2 * expected_len
+        );
+
+        let num_inputs = cs.num_instance_variables();
+        let a =
+            EmulatedFpVar::<TargetF, BaseF>::new_packed_input(cs.clone(), || Ok(a_native)).unwrap();
+        assert_eq!(cs.num_instance_variables() - num_inputs, expected_len);
+        assert_eq!(a.value().unwrap(), a_native);
+
+        let b = EmulatedFpVar::<TargetF, BaseF>::new_witness(cs.clone(), || Ok(b_native)).unwrap();
+        let c = &a * &b + &a;
+        assert_eq!(c.value().unwrap(), a_native * b_native + a_native);
+        a.enforce_equal(&EmulatedFpVar::new_input(cs.clone(), || Ok(a_native)).unwrap())
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
+
+fn cmp_test<TargetF: PrimeField, BaseField: PrimeField, R: RngCore>(
+    cs: ConstraintSystemRef<BaseField>,
+    rng: &mut R,
+) {
+    let mut values = vec![TargetF::zero(), TargetF::one(), -TargetF::one()];
+    values.push(TargetF::rand(rng));
+
+    for a_native in &values {
+        let a =
+            EmulatedFpVar::<TargetF, BaseField>::new_witness(cs.clone(), || Ok(a_native)).unwrap();
+        assert_eq!(
+            a.to_bits_le().unwrap().len(),
+            TargetF::MODULUS_BIT_SIZE as usize
+        );
+        for b_native in &values {
+            let b_var = EmulatedFpVar::new_witness(cs.clone(), || Ok(b_native)).unwrap();
+            let b_const = EmulatedFpVar::Constant(*b_native);
+            for b in [&b_var, &b_const] {
+                assert_eq!(a.is_lt(b).unwrap().value().unwrap(), a_native < b_native);
+                assert_eq!(a.is_le(b).unwrap().value().unwrap(), a_native <= b_native);
+                assert_eq!(a.is_gt(b).unwrap().value().unwrap(), a_native > b_native);
+                assert_eq!(a.is_ge(b).unwrap().value().unwrap(), a_native >= b_native);
+            }
+        }
+    }
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn emulated_cmp_test() {
+    let rng = &mut ark_std::test_rng();
+
+    let cs = ConstraintSystem::<ark_bn254::Fr>::new_ref();
+    cmp_test::<ark_bls12_381::Fq, _, _>(cs, rng);
+
+    // The bits of the target field span more than two comparison chunks.
+    let cs = ConstraintSystem::<ark_bn254::Fr>::new_ref();
+    cmp_test::<<MNT6_753 as Pairing>::BaseField, _, _>(cs, rng);
+}
+
+#[test]
+fn emulated_enforce_canonical_test() {
+    type TargetF = ark_bls12_381::Fr;
+    type BaseF = ark_bn254::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    let cs = ConstraintSystem::<BaseF>::new_ref();
+
+    // An ECDSA-style range condition `0 < r < n` on a scalar.
+    let r_native = TargetF::rand(rng);
+    let mut r = EmulatedFpVar::<TargetF, BaseF>::new_witness(cs.clone(), || Ok(r_native)).unwrap();
+    r.enforce_canonical().unwrap();
+    r.is_gt(&EmulatedFpVar::zero())
+        .unwrap()
+        .enforce_equal(&Boolean::TRUE)
+        .unwrap();
+    match &r {
+        EmulatedFpVar::Var(v) => assert!(v.is_in_the_normal_form),
+        EmulatedFpVar::Constant(_) => panic!("expected a variable"),
+    }
+    assert_eq!((&r * &r).value().unwrap(), r_native.square());
+    assert!(cs.is_satisfied().unwrap());
+
+    // Limbs that encode `1 + n` instead of `1` are rejected.
+    let cs = ConstraintSystem::<BaseF>::new_ref();
+    let params = AllocatedEmulatedFpVar::<TargetF, BaseF>::default_params(&cs);
+    let mut one_plus_n = TargetF::MODULUS;
+    assert!(!one_plus_n.add_with_carry(&1u64.into()));
+    let limbs = AllocatedEmulatedFpVar::<TargetF, BaseF>::get_limbs_representations_from_big_integer_with_params(
+        &one_plus_n,
+        &params,
+    )
+    .unwrap()
+    .into_iter()
+    .map(|limb| FpVar::new_witness(cs.clone(), || Ok(limb)).unwrap())
+    .collect();
+    let mut one = AllocatedEmulatedFpVar::<TargetF, BaseF> {
+        cs: cs.clone(),
+        limbs,
+        num_of_additions_over_normal_form: BaseF::zero(),
+        is_in_the_normal_form: true,
+        params,
+        target_phantom: PhantomData,
+    };
+    assert_eq!(one.value().unwrap(), TargetF::one());
+    one.enforce_canonical().unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn emulated_long_sum_of_products_test() {
+    type TargetF = ark_bls12_381::Fq;
+    type BaseF = ark_bn254::Fr;
+
+    let rng = &mut ark_std::test_rng();
+    for goal in [OptimizationGoal::Constraints, OptimizationGoal::Weight] {
+        let cs = ConstraintSystem::<BaseF>::new_ref();
+        cs.set_optimization_goal(goal);
+
+        // With limbs this wide, the accumulated limbs of this many products
+        // must be reduced in between.
+        let wide_limbs = NonNativeFieldConfig {
+            num_limbs: 4,
+            bits_per_limb: 120,
+        };
+        let mut wide_terms = Vec::new();
+        let mut expected = TargetF::zero();
+        for _ in 0..100 {
+            let a_native = TargetF::rand(rng);
+            let b_native = TargetF::rand(rng);
+            let alloc = |v: TargetF| {
+                EmulatedFpVar::<TargetF, BaseF>::new_variable_with_params(
+                    cs.clone(),
+                    || Ok(v),
+                    AllocationMode::Witness,
+                    wide_limbs,
+                )
+                .unwrap()
+            };
+            expected += a_native * b_native;
+            wide_terms.push((alloc(a_native), alloc(b_native)));
+        }
+        let sum = EmulatedFpVar::<TargetF, BaseF>::sum_of_products(&wide_terms).unwrap();
+        assert_eq!(sum.value().unwrap(), expected);
+        assert!(cs.is_satisfied().unwrap());
+
+        let terms = (0..4)
+            .map(|_| {
+                let a = EmulatedFpVar::<TargetF, BaseF>::new_witness(cs.clone(), || {
+                    Ok(TargetF::rand(rng))
+                })
+                .unwrap();
+                let b = EmulatedFpVar::<TargetF, BaseF>::new_witness(cs.clone(), || {
+                    Ok(TargetF::rand(rng))
+                })
+                .unwrap();
+                (a, b)
+            })
+            .collect::<Vec<_>>();
+        // A short sum is cheaper than reducing every product.
+        let (lazy, lazy_cost) =
+            constraints_added(&cs, || EmulatedFpVar::sum_of_products(&terms).unwrap());
+        let (eager, eager_cost) = constraints_added(&cs, || {
+            terms
+                .iter()
+                .fold(EmulatedFpVar::zero(), |acc, (a, b)| acc + a * b)
+        });
+
+        assert_eq!(lazy.value().unwrap(), eager.value().unwrap());
+        assert!(lazy_cost < eager_cost);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
+
+#[test]
+fn emulated_small_base_field_params_test() {
+    for goal in [OptimizationGoal::Constraints, OptimizationGoal::Weight] {
+        let params = params_for_goal::<ark_bn254::Fr, Goldilocks>(goal);
+        assert!(params.is_valid_for::<ark_bn254::Fr, Goldilocks>());
+        let params = params_for_goal::<ark_bn254::Fr, BabyBear>(goal);
+        assert!(params.is_valid_for::<ark_bn254::Fr, BabyBear>());
+        let params = params_for_goal::<ark_bn254::Fr, Mersenne31>(goal);
+        assert!(params.is_valid_for::<ark_bn254::Fr, Mersenne31>());
+    }
+
+    // A long chain of multiplications keeps the limbs within BaseF
+    let rng = &mut ark_std::test_rng();
+    let cs = ConstraintSystem::<BabyBear>::new_ref();
+    let mut value = ark_bn254::Fr::rand(rng);
+    let mut var =
+        EmulatedFpVar::<ark_bn254::Fr, BabyBear>::new_witness(cs.clone(), || Ok(value)).unwrap();
+    for _ in 0..10 {
+        let other = ark_bn254::Fr::rand(rng);
+        let other_var =
+            EmulatedFpVar::<ark_bn254::Fr, BabyBear>::new_witness(cs.clone(), || Ok(other))
+                .unwrap();
+        var = (&var + &other_var) * &other_var;
+        value = (value + other) * other;
+    }
+    assert_eq!(var.value().unwrap(), value);
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn emulated_small_base_field_layout_cost_test() {
+    // Nonzeros are not estimated when optimizing for weight, so only the
+    // layouts for constraints are compared.
+    fn layout_cost_test<TargetF: PrimeField, BaseF: PrimeField>() {
+        let goal = OptimizationGoal::Constraints;
+        let mul_cost = |params| {
+            estimate_costs_with_params::<TargetF, BaseF>(params, goal.into())
+                .mul
+                .constraints
+        };
+        let chosen = mul_cost(params_for_goal::<TargetF, BaseF>(goal));
+
+        let target_bits = TargetF::MODULUS_BIT_SIZE as usize;
+        for bits_per_limb in 1..=target_bits {
+            let params = NonNativeFieldConfig {
+                num_limbs: (target_bits + bits_per_limb - 1) / bits_per_limb,
+                bits_per_limb,
+            };
+            if params.is_valid_for::<TargetF, BaseF>() {
+                assert!(chosen <= mul_cost(params), "{:?} is cheaper", params);
+            }
+        }
+    }
+    layout_cost_test::<ark_bn254::Fr, Goldilocks>();
+    layout_cost_test::<ark_bn254::Fr, BabyBear>();
+}
+
+#[test]
+#[should_panic(expected = "the base field is too small")]
+fn emulated_tiny_base_field_params_test() {
+    ark_r1cs_std::fields::emulated_fp::params::find_parameters(
+        12,
+        254,
+        ark_r1cs_std::fields::emulated_fp::params::OptimizationType::Constraints,
+    );
+}
+
+#[test]
+fn emulated_single_limb_test() {
+    let params = params_for_goal::<Goldilocks, ark_bn254::Fr>(OptimizationGoal::Constraints);
+    assert_eq!(params.num_limbs, 1);
+
+    let mul_cost = |params: NonNativeFieldConfig| {
+        let rng = &mut ark_std::test_rng();
+        let cs = ConstraintSystem::<ark_bn254::Fr>::new_ref();
+        let a = Goldilocks::rand(rng);
+        let b = Goldilocks::rand(rng);
+        let a_var = EmulatedFpVar::new_variable_with_params(
+            cs.clone(),
+            || Ok(a),
+            AllocationMode::Witness,
+            params,
+        )
+        .unwrap();
+        let b_var = EmulatedFpVar::new_variable_with_params(
+            cs.clone(),
+            || Ok(b),
+            AllocationMode::Witness,
+            params,
+        )
+        .unwrap();
+        let (c_var, cost) = constraints_added(&cs, || (&a_var + &b_var) * &b_var);
+        assert_eq!(c_var.value().unwrap(), (a + b) * b);
+        assert!(cs.is_satisfied().unwrap());
+        cost
+    };
+
+    let two_limbs = NonNativeFieldConfig {
+        num_limbs: 2,
+        bits_per_limb: 32,
+    };
+    assert!(mul_cost(params) < mul_cost(two_limbs));
+}
+
+#[test]
+fn emulated_packed_test() {
+    let rng = &mut ark_std::test_rng();
+    let cs = ConstraintSystem::<ark_bn254::Fr>::new_ref();
+    let capacity = PackedEmulatedFpVar::<BabyBear, ark_bn254::Fr>::capacity();
+    assert!(capacity > 1);
+
+    let mut values: Vec<BabyBear> = (0..capacity).map(|_| BabyBear::rand(rng)).collect();
+    let mut vars: Vec<EmulatedFpVar<BabyBear, ark_bn254::Fr>> = values
+        .iter()
+        .map(|v| EmulatedFpVar::new_witness(cs.clone(), || Ok(*v)).unwrap())
+        .collect();
+    // Mix in a constant
+    values[0] = BabyBear::from(7u64);
+    vars[0] = EmulatedFpVar::Constant(values[0]);
+    let packed = PackedEmulatedFpVar::pack(&vars).unwrap();
+    assert_eq!(packed.value().unwrap(), values);
+
+    // Enough additions to require reducing the slots in between
+    let mut sum = packed.clone();
+    let mut expected = values.clone();
+    for _ in 0..300 {
+        sum = sum.add(&packed).unwrap();
+        for (e, v) in expected.iter_mut().zip(&values) {
+            *e += v;
+        }
+    }
+    assert_eq!(sum.value().unwrap(), expected);
+
+    let unpacked = sum.unpack().unwrap();
+    for ((var, value), e) in unpacked.iter().zip(&values).zip(&expected) {
+        assert_eq!(var.value().unwrap(), *e);
+        assert_eq!((var * value).value().unwrap(), *e * value);
+    }
+    assert!(cs.is_satisfied().unwrap());
+}
+
+/// Measures the cost of `op` on two freshly allocated (equal) witnesses in the
+/// limb layout `params`.
+fn measure_cost<TargetF: PrimeField, BaseField: PrimeField>(
+    goal: OptimizationGoal,
+    params: NonNativeFieldConfig,
+    op: impl FnOnce(&EmulatedFpVar<TargetF, BaseField>, &EmulatedFpVar<TargetF, BaseField>),
+) -> Cost {
+    let rng = &mut ark_std::test_rng();
+    let cs = ConstraintSystem::<BaseField>::new_ref();
+    cs.set_optimization_goal(goal);
+    let value = TargetF::rand(rng);
+    let alloc = || {
+        EmulatedFpVar::new_variable_with_params(
+            cs.clone(),
+            || Ok(value),
+            AllocationMode::Witness,
+            params,
+        )
+        .unwrap()
+    };
+    let a = alloc();
+    let b = alloc();
+
+    let ((), cost) = cost_added(&cs, || op(&a, &b));
+    assert!(cs.is_satisfied().unwrap());
+    cost
+}
+
+fn estimate_costs_test<TargetF: PrimeField, BaseField: PrimeField>(
+    goal: OptimizationGoal,
+    params: NonNativeFieldConfig,
+) {
+    let estimates = estimate_costs_with_params::<TargetF, BaseField>(params, goal.into());
+    assert_eq!(estimates.params, params);
+
+    let measured = [
+        (
+            estimates.alloc,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, _| {
+                let _ = EmulatedFpVar::<TargetF, BaseField>::new_variable_with_params(
+                    a.cs(),
+                    || a.value(),
+                    AllocationMode::Witness,
+                    params,
+                )
+                .unwrap();
+            }),
+        ),
+        (
+            estimates.add,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, b| {
+                let _ = a + b;
+            }),
+        ),
+        (
+            estimates.mul,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, b| {
+                let _ = a * b;
+            }),
+        ),
+        (
+            estimates.mul_without_reduce,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, b| {
+                let _ = a.mul_without_reduce(b).unwrap();
+            }),
+        ),
+        (
+            estimates.mul_without_reduce + estimates.reduce,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, b| {
+                let _ = a.mul_without_reduce(b).unwrap().reduce().unwrap();
+            }),
+        ),
+        (
+            estimates.inverse,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, _| {
+                let _ = a.inverse().unwrap();
+            }),
+        ),
+        (
+            estimates.enforce_equal,
+            measure_cost::<TargetF, BaseField>(goal, params, |a, b| {
+                a.enforce_equal(b).unwrap();
+            }),
+        ),
+    ];
+
+    let within = |estimate: usize, measured: usize| estimate.abs_diff(measured) * 10 <= measured;
+    for (estimate, measured) in measured {
+        assert!(
+            within(estimate.constraints, measured.constraints)
+                && within(estimate.witness_variables, measured.witness_variables),
+            "estimated {:?}, measured {:?}",
+            estimate,
+            measured
+        );
+        // Nonzeros are not estimated for `OptimizationType::Weight`; see
+        // `OperationCost::nonzeros`.
+        assert_eq!(
+            estimate.nonzeros.is_some(),
+            goal != OptimizationGoal::Weight
+        );
+        if let Some(nonzeros) = estimate.nonzeros {
+            assert!(
+                within(nonzeros, measured.nonzeros),
+                "estimated {:?}, measured {:?}",
+                estimate,
+                measured
+            );
+        }
+    }
+}
+
+#[test]
+fn emulated_estimate_costs_test() {
+    type Fq = <<Bls12_381 as Pairing>::G1 as CurveGroup>::BaseField;
+    type Fr = <Bls12_381 as Pairing>::ScalarField;
+    fn default_params_test<TargetF: PrimeField, BaseField: PrimeField>(goal: OptimizationGoal) {
+        let params = params_for_goal::<TargetF, BaseField>(goal);
+        assert_eq!(
+            estimate_costs::<TargetF, BaseField>(goal.into()).params,
+            params
+        );
+        estimate_costs_test::<TargetF, BaseField>(goal, params);
+    }
+    default_params_test::<Fq, Fr>(OptimizationGoal::Constraints);
+    default_params_test::<Fq, Fr>(OptimizationGoal::Weight);
+    default_params_test::<Goldilocks, ark_bn254::Fr>(OptimizationGoal::Constraints);
+    default_params_test::<Fr, <MNT4_298 as Pairing>::ScalarField>(OptimizationGoal::Constraints);
+
+    // Small base fields, in the default layout and in one with wider limbs
+    default_params_test::<ark_bn254::Fr, Goldilocks>(OptimizationGoal::Constraints);
+    default_params_test::<ark_bn254::Fr, Goldilocks>(OptimizationGoal::Weight);
+    let wide_limbs = NonNativeFieldConfig {
+        num_limbs: 22,
+        bits_per_limb: 12,
+    };
+    assert!(wide_limbs.is_valid_for::<ark_bn254::Fr, Goldilocks>());
+    estimate_costs_test::<ark_bn254::Fr, Goldilocks>(OptimizationGoal::Constraints, wide_limbs);
+}
+
+#[test]
+fn emulated_fpvar_conversion_test() {
+    use ark_pallas::{Fq, Fr};
+    let rng = &mut ark_std::test_rng();
+
+    // `Fr` is larger than `Fq`, so every `Fq` element is also an `Fr` element.
+    let cs = ConstraintSystem::<Fq>::new_ref();
+    let x = Fq::rand(rng);
+    let x_var = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+    let x_fr: Fr = Fr::from(num_bigint::BigUint::from(x));
+    let emulated = EmulatedFpVar::<Fr, Fq>::from_fpvar(&x_var).unwrap();
+    assert_eq!(emulated.value().unwrap(), x_fr);
+    let y = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(Fr::from(3u8))).unwrap();
+    let sum = (&emulated + &y).to_fpvar().unwrap();
+    sum.enforce_equal(&(&x_var + Fq::from(3u8))).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    let bits = x_var.to_bits_le().unwrap();
+    let from_bits = EmulatedFpVar::<Fr, Fq>::from_fpvar_bits(&bits).unwrap();
+    from_bits.enforce_equal(&emulated).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    let constant =
+        EmulatedFpVar::<Fr, Fq>::from_fpvar_bits(&[Boolean::TRUE, Boolean::FALSE, Boolean::TRUE])
+            .unwrap();
+    assert!(constant.is_constant());
+    assert_eq!(constant.value().unwrap(), Fr::from(5u8));
+
+    // Values of at least `Fq::MODULUS` do not fit.
+    let cs = ConstraintSystem::<Fq>::new_ref();
+    let too_large = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(-Fr::one())).unwrap();
+    let _ = too_large.to_fpvar().unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+    assert!(EmulatedFpVar::<Fr, Fq>::constant(-Fr::one())
+        .to_fpvar()
+        .is_err());
+}